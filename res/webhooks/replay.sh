@@ -0,0 +1,17 @@
+#!/bin/sh
+# Replays a captured webhook payload against the local receiver, signed the way Trello does.
+# usage: replay.sh <payload.json> <secret> <callback_url> [port]
+set -e
+payload=$1
+secret=$2
+callback_url=$3
+port=${4:-9998}
+
+signature=$( (cat "$payload"; printf '%s' "$callback_url") \
+    | openssl dgst -sha1 -hmac "$secret" -binary | base64)
+
+curl -s -o /dev/null -w '%{http_code}\n' \
+    -H "X-Trello-Webhook: $signature" \
+    -H 'Content-Type: application/json' \
+    --data-binary @"$payload" \
+    "http://localhost:$port/webhook"
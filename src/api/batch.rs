@@ -0,0 +1,31 @@
+use super::{Request, Trello};
+use serde::{de::DeserializeOwned, Deserialize};
+
+const URL_BASE: &str = "/batch";
+
+// the most routes Trello accepts in one batch
+pub const BATCH_LIMIT: usize = 10;
+
+// One answer of a batch, keyed by its status. Failed routes carry no "200" and get skipped.
+#[derive(Deserialize)]
+pub struct BatchEntry<T> {
+    #[serde(rename = "200")]
+    pub ok: Option<T>,
+}
+
+pub trait Batch {
+    // `urls` are api routes without the endpoint, e.g. `/organizations/{id}`
+    fn batch<T>(&self, urls: &[String]) -> Request<Vec<BatchEntry<T>>>
+    where
+        T: DeserializeOwned;
+}
+
+impl Batch for Trello {
+    fn batch<T>(&self, urls: &[String]) -> Request<Vec<BatchEntry<T>>>
+    where
+        T: DeserializeOwned,
+    {
+        let url = format!("{}{}", self.endpoint, URL_BASE);
+        Request::get(url, self).with_query("urls", &urls.join(","))
+    }
+}
@@ -1,38 +1,60 @@
-use super::{Api, Request, RequestFields, ENDPOINT};
-use crate::models::{BoardId, Card, List};
-use const_format::formatcp;
+use super::{fields, Request, RequestFields, SendRequestError, Trello};
+use crate::models::{Board, BoardContent, BoardId, Card, Label, List};
 
-const URL_BASE: &str = formatcp!("{}/boards", ENDPOINT);
-const URL_LISTS: &str = "lists";
+const URL_BASE: &str = "/boards";
 const URL_CARDS: &str = "cards";
 
+// the most cards Trello hands out in one response
+pub const CARDS_PAGE_LIMIT: usize = 1000;
+
 pub trait Boards {
-    fn boards_lists(&self, id: &BoardId) -> Request<Vec<List>>;
+    fn boards_get(&self, id: &BoardId) -> Request<Board>;
+    fn boards_content(&self, id: &BoardId) -> Request<BoardContent>;
     fn boards_cards(&self, id: &BoardId) -> Request<Vec<Card>>;
 }
 
-impl Boards for Api {
-    fn boards_lists(&self, id: &BoardId) -> Request<Vec<List>> {
-        let fetch_user_url = format!("{}/{}/{}", URL_BASE, id, URL_LISTS);
-        self.get_req(
-            fetch_user_url,
-            RequestFields::List(vec!["id", "name", "idBoard", "idOrganization"]),
-        )
+impl Boards for Trello {
+    fn boards_get(&self, id: &BoardId) -> Request<Board> {
+        let fetch_board_url = format!("{}/{}", URL_BASE, id);
+        self.get_req(fetch_board_url, RequestFields::of::<Board>())
+    }
+
+    // the board with its open lists, open cards and labels in a single round trip
+    fn boards_content(&self, id: &BoardId) -> Request<BoardContent> {
+        let fetch_board_url = format!("{}/{}", URL_BASE, id);
+        self.get_req(fetch_board_url, RequestFields::of::<Board>())
+            .with_query("lists", "open")
+            .with_query("list_fields", &fields::of::<List>().join(","))
+            .with_query("cards", "open")
+            .with_query("card_fields", &fields::of::<Card>().join(","))
+            .with_query("labels", "all")
+            .with_query("label_fields", &fields::of::<Label>().join(","))
     }
 
+    // the first page, `page_cards` goes through the rest
     fn boards_cards(&self, id: &BoardId) -> Request<Vec<Card>> {
         let fetch_user_url = format!("{}/{}/{}", URL_BASE, id, URL_CARDS);
-        self.get_req(
-            fetch_user_url,
-            RequestFields::List(vec![
-                "id",
-                "name",
-                "idBoard",
-                "idOrganization",
-                "idList",
-                "labels",
-                "url",
-            ]),
-        )
+        self.get_req(fetch_user_url, RequestFields::of::<Card>())
+            .with_query("limit", &CARDS_PAGE_LIMIT.to_string())
+    }
+}
+
+// Keeps asking for the cards older than the oldest one so far for as long as the pages
+// come back full. `page` is a `boards_cards` request.
+pub async fn page_cards(
+    mut cards: Vec<Card>,
+    page: Request<Vec<Card>>,
+) -> Result<Vec<Card>, SendRequestError> {
+    let mut last_page = cards.len();
+    while last_page >= CARDS_PAGE_LIMIT {
+        // ids start with their creation time, the smallest is the oldest
+        let before = match cards.iter().map(|card| &card.id).min() {
+            Some(id) => id.clone(),
+            None => break,
+        };
+        let older = page.with_query("before", before.as_str()).send().await?;
+        last_page = older.len();
+        cards.extend(older);
     }
+    Ok(cards)
 }
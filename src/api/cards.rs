@@ -0,0 +1,58 @@
+use super::{Request, RequestFields, RequestProtocol, Trello};
+use crate::models::{Card, CardId, ListId};
+
+const URL_BASE: &str = "/cards";
+const URL_COMMENTS: &str = "actions/comments";
+
+pub trait Cards {
+    fn cards_get(&self, id: &CardId) -> Request<Card>;
+    fn cards_create(&self, id_list: &ListId, name: &str) -> Request<Card>;
+    fn cards_update(
+        &self,
+        id: &CardId,
+        name: Option<&String>,
+        id_list: Option<&ListId>,
+    ) -> Request<Card>;
+    fn cards_comment(&self, id: &CardId, text: &str) -> Request<serde_json::Value>;
+}
+
+impl Cards for Trello {
+    fn cards_get(&self, id: &CardId) -> Request<Card> {
+        let fetch_card_url = format!("{}/{}", URL_BASE, id);
+        self.get_req(fetch_card_url, RequestFields::of::<Card>())
+    }
+
+    fn cards_create(&self, id_list: &ListId, name: &str) -> Request<Card> {
+        self.write_req(
+            URL_BASE.to_string(),
+            RequestProtocol::POST,
+            vec![("idList", id_list.to_string()), ("name", name.to_string())],
+        )
+    }
+
+    fn cards_update(
+        &self,
+        id: &CardId,
+        name: Option<&String>,
+        id_list: Option<&ListId>,
+    ) -> Request<Card> {
+        let update_card_url = format!("{}/{}", URL_BASE, id);
+        let params = [
+            ("name", name.cloned()),
+            ("idList", id_list.map(ListId::to_string)),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|v| (key, v)))
+        .collect();
+        self.write_req(update_card_url, RequestProtocol::PUT, params)
+    }
+
+    fn cards_comment(&self, id: &CardId, text: &str) -> Request<serde_json::Value> {
+        let comment_url = format!("{}/{}/{}", URL_BASE, id, URL_COMMENTS);
+        self.write_req(
+            comment_url,
+            RequestProtocol::POST,
+            vec![("text", text.to_string())],
+        )
+    }
+}
@@ -0,0 +1,57 @@
+use crate::database::NetworkConfig;
+use crate::APP_NAME;
+use const_format::formatcp;
+use reqwest::{Certificate, Client, Proxy};
+use std::{fs, io::Error as IoError, time::Duration};
+use thiserror::Error;
+
+const USER_AGENT: &str = formatcp!("{}/{}", APP_NAME, env!("CARGO_PKG_VERSION"));
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Can't read the CA bundle {0}: {1}")]
+    CaBundle(String, IoError),
+    #[error("Invalid certificate in {0}: {1}")]
+    Certificate(String, reqwest::Error),
+    #[error("Invalid proxy {0}: {1}")]
+    Proxy(String, reqwest::Error),
+    #[error(transparent)]
+    Build(#[from] reqwest::Error),
+}
+
+fn certificates(path: &str) -> Result<Vec<Certificate>, ClientError> {
+    let bundle =
+        fs::read_to_string(path).map_err(|err| ClientError::CaBundle(path.to_string(), err))?;
+    // a bundle is just the certificates one after the other
+    bundle
+        .split_inclusive(PEM_END)
+        .filter(|pem| pem.contains(PEM_END))
+        .map(|pem| {
+            Certificate::from_pem(pem.trim().as_bytes())
+                .map_err(|err| ClientError::Certificate(path.to_string(), err))
+        })
+        .collect()
+}
+
+// The client shared by every request, it keeps the connections to Trello alive between them.
+// reqwest 0.11 can't time out a stalled read on its own, only the request as a whole, so a dead
+// server is caught by `connect_timeout` and `timeout` is just the last resort.
+pub fn build(config: &NetworkConfig) -> Result<Client, ClientError> {
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(Duration::from_secs(config.connect_timeout))
+        .timeout(Duration::from_secs(config.timeout))
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT);
+    if let Some(proxy) = &config.proxy {
+        let proxy = Proxy::all(proxy).map_err(|err| ClientError::Proxy(proxy.clone(), err))?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = &config.ca_bundle {
+        for certificate in certificates(path)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder.build()?)
+}
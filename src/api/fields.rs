@@ -0,0 +1,53 @@
+use serde::{
+    de::{self, value::Error, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+// Goes through the motions of deserializing a struct only to catch the field names serde
+// asks for, renames included. Anything that isn't a plain struct has none.
+struct Introspect<'a> {
+    fields: &'a mut &'static [&'static str],
+}
+
+impl<'de, 'a> Deserializer<'de> for Introspect<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.fields = fields;
+        Err(de::Error::custom("fields caught"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+// The names a model is deserialized from, exactly what has to go in its `fields=`.
+pub fn of<'de, T>() -> &'static [&'static str]
+where
+    T: Deserialize<'de>,
+{
+    let mut fields: &'static [&'static str] = &[];
+    T::deserialize(Introspect {
+        fields: &mut fields,
+    })
+    .ok();
+    fields
+}
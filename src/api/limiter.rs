@@ -0,0 +1,100 @@
+use rand::Rng;
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+// Trello allows 100 requests per 10 seconds for every token
+const BUCKET_SIZE: f64 = 100.0;
+const REFILL_RATE: f64 = BUCKET_SIZE / 10.0;
+// requests in flight at once, across every page
+const MAX_CONCURRENT: usize = 6;
+
+const MAX_RETRIES: u32 = 4;
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    // takes a token, or tells how long to wait for the next one
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * REFILL_RATE).min(BUCKET_SIZE);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / REFILL_RATE))
+        }
+    }
+}
+
+// Keeps every request within Trello's rate limits. Only one token is in use at a time
+// so a single bucket is enough. Cheap to clone, all clones share the same limits.
+#[derive(Clone)]
+pub struct Limiter {
+    bucket: Arc<Mutex<Bucket>>,
+    in_flight: Arc<Semaphore>,
+}
+
+impl Limiter {
+    pub fn new() -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: BUCKET_SIZE,
+                refilled_at: Instant::now(),
+            })),
+            in_flight: Arc::new(Semaphore::new(MAX_CONCURRENT)),
+        }
+    }
+
+    // waits for a free slot and a token, the request goes out while the permit is held
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit = self
+            .in_flight
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        loop {
+            let wait = self.bucket.lock().unwrap().take();
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return permit,
+            }
+        }
+    }
+}
+
+// a 429 never reached the handler, a 5xx may have only failed on the way back
+pub fn should_retry(resp: &Response, attempt: u32, idempotent: bool) -> bool {
+    let status = resp.status();
+    attempt < MAX_RETRIES
+        && (status == StatusCode::TOO_MANY_REQUESTS || (idempotent && status.is_server_error()))
+}
+
+// how long to wait before the next attempt, the server knows best when it says so,
+// otherwise exponential backoff with full jitter
+pub fn backoff(resp: &Response, attempt: u32) -> Duration {
+    let retry_after = resp
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+    match retry_after {
+        Some(retry_after) => retry_after.min(BACKOFF_MAX),
+        None => {
+            let ceiling = BACKOFF_BASE.saturating_mul(2u32.saturating_pow(attempt));
+            let ceiling = ceiling.min(BACKOFF_MAX);
+            rand::thread_rng().gen_range(Duration::ZERO..=ceiling)
+        }
+    }
+}
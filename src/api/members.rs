@@ -1,25 +1,16 @@
-use super::{Api, Request, RequestFields, ENDPOINT};
+use super::{Request, RequestFields, Trello};
 use crate::models::User;
 use const_format::formatcp;
 
-const URL_BASE: &str = formatcp!("{}/members", ENDPOINT);
+const URL_BASE: &str = "/members";
 const URL_ME: &str = formatcp!("{}/me", URL_BASE);
 
 pub trait Members {
     fn members_me(&self) -> Request<User>;
 }
 
-impl Members for Api {
+impl Members for Trello {
     fn members_me(&self) -> Request<User> {
-        self.get_req(
-            URL_ME.to_string(),
-            RequestFields::List(vec![
-                "id",
-                "username",
-                "fullName",
-                "idBoards",
-                "idOrganizations",
-            ]),
-        )
+        self.get_req(URL_ME.to_string(), RequestFields::of::<User>())
     }
 }
@@ -1,36 +1,142 @@
+pub mod batch;
 pub mod boards;
+pub mod cards;
+pub mod client;
+mod fields;
+mod limiter;
 pub mod members;
 pub mod organizations;
+pub mod webhooks;
 
-use std::marker::PhantomData;
+use crate::backend::{Backend, Local};
+use crate::cache::Cache;
+use crate::models::UserId;
+use crate::outbox::Outbox;
+use limiter::Limiter;
+use reqwest::{
+    header::{HeaderValue, AUTHORIZATION},
+    StatusCode,
+};
+use std::{
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 use thiserror::Error;
 
-const ENDPOINT: &str = "https://api.trello.com/1";
+pub const DEFAULT_ENDPOINT: &str = "https://api.trello.com/1";
+pub const INVALID_TOKEN: &str = "the token has characters that can't be sent";
 
 pub struct Api {
+    // the one in use, either `remote` or the boards of a local account
+    backend: Arc<dyn Backend>,
+    remote: Arc<dyn Backend>,
+    cache: Cache,
+    outbox: Outbox,
+    // where local accounts keep their boards
+    local_dir: String,
+}
+
+// The client for Trello's REST API, the resource traits build their requests from it.
+pub struct Trello {
     key: String,
-    token: String,
+    token: RwLock<String>,
+    // base url every path gets appended to
+    endpoint: String,
+    client: reqwest::Client,
+    limiter: Limiter,
 }
 
 #[derive(Error, Debug)]
 pub enum SendRequestError {
     #[error("Token Expired")]
     ExpiredToken,
+    // the token is invalid or got revoked
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    #[error("Not Found: {0}")]
+    NotFound(String),
+    #[error("Rate Limited: {0}")]
+    RateLimited(String),
+    #[error("Server Error {0}: {1}")]
+    ServerError(u16, String),
+    #[error("Request Failed {0}: {1}")]
+    StatusError(u16, String),
     #[error(transparent)]
-    RequestError(#[from] reqwest::Error),
+    RequestError(reqwest::Error),
     #[error(transparent)]
     SerializationError(#[from] serde_json::Error),
 }
 
+// reqwest puts the url in its errors, keep it out of anything shown or logged
+impl From<reqwest::Error> for SendRequestError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::RequestError(err.without_url())
+    }
+}
+
+impl SendRequestError {
+    // what an unsuccessful answer means, whichever backend it came from
+    pub fn from_status(status: StatusCode, message: String) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED if message == "expired token" => Self::ExpiredToken,
+            StatusCode::UNAUTHORIZED => Self::Unauthorized(message),
+            StatusCode::FORBIDDEN => Self::Forbidden(message),
+            StatusCode::NOT_FOUND => Self::NotFound(message),
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited(message),
+            status if status.is_server_error() => Self::ServerError(status.as_u16(), message),
+            status => Self::StatusError(status.as_u16(), message),
+        }
+    }
+
+    // worth trying again later, nothing is wrong with the request itself
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::RequestError(_) | Self::RateLimited(_) | Self::ServerError(..)
+        )
+    }
+
+    // no answer says whether the request went through, it might have been applied anyway
+    pub fn is_ambiguous(&self) -> bool {
+        match self {
+            Self::ServerError(..) => true,
+            Self::RequestError(err) => !err.is_connect() && !err.is_builder(),
+            _ => false,
+        }
+    }
+}
+
 pub enum RequestFields<'a> {
-    All,
-    Raw(&'a str),
     List(Vec<&'a str>),
 }
 
+impl RequestFields<'static> {
+    // exactly what `Model` gets deserialized from, so a field added to it gets requested too
+    pub fn of<'de, Model>() -> Self
+    where
+        Model: serde::Deserialize<'de>,
+    {
+        Self::List(fields::of::<Model>().to_vec())
+    }
+}
+
+#[derive(Clone)]
 pub enum RequestProtocol {
     GET,
     POST,
+    PUT,
+    DELETE,
+}
+
+impl RequestProtocol {
+    // a POST that failed on the server may still have gone through, sending it again could
+    // create the same card twice
+    fn is_idempotent(&self) -> bool {
+        !matches!(self, Self::POST)
+    }
 }
 
 pub struct Request<Response>
@@ -40,58 +146,164 @@ where
     _resp: PhantomData<Response>,
     url: String,
     protocol: RequestProtocol,
+    // nothing when the token can't be put in a header
+    authorization: Option<HeaderValue>,
+    client: reqwest::Client,
+    limiter: Limiter,
 }
 
 impl<Response> Request<Response>
 where
     Response: serde::de::DeserializeOwned,
 {
-    fn new(url: String, protocol: RequestProtocol) -> Self {
+    fn new(url: String, protocol: RequestProtocol, api: &Trello) -> Self {
         Self {
             _resp: PhantomData,
             url,
             protocol,
+            authorization: api.authorization(),
+            client: api.client.clone(),
+            limiter: api.limiter.clone(),
         }
     }
 
-    pub fn get(url: String) -> Self {
-        Self::new(url, RequestProtocol::GET)
+    pub fn get(url: String, api: &Trello) -> Self {
+        Self::new(url, RequestProtocol::GET, api)
     }
 
-    pub async fn send(self) -> Result<Response, SendRequestError> {
-        match self.protocol {
-            RequestProtocol::GET => self.send_get().await,
-            RequestProtocol::POST => self.send_post().await,
+    // the same request with one more query parameter, for follow-up pages
+    pub fn with_query(&self, name: &str, value: &str) -> Self {
+        let mut url = self.url.clone();
+        if let Ok(mut parsed) = reqwest::Url::parse(&self.url) {
+            parsed.query_pairs_mut().append_pair(name, value);
+            url = parsed.to_string();
+        }
+        Self {
+            _resp: PhantomData,
+            url,
+            protocol: self.protocol.clone(),
+            authorization: self.authorization.clone(),
+            client: self.client.clone(),
+            limiter: self.limiter.clone(),
         }
     }
 
-    async fn send_get(self) -> Result<Response, SendRequestError> {
-        let body = reqwest::get(self.url).await?.text().await?;
-        let body: &str = body.as_str();
-        match body {
-            "expired token" => Err(SendRequestError::ExpiredToken),
-            _ => {
-                let response: Response = serde_json::from_str(body)?;
-                Ok(response)
+    // retries on 429, and on 5xx when sending again is safe. Every attempt goes through the
+    // rate limiter.
+    pub async fn send(self) -> Result<Response, SendRequestError> {
+        let mut attempt = 0;
+        loop {
+            let resp = {
+                let _permit = self.limiter.acquire().await;
+                self.build()?.send().await?
+            }; // release the slot while backing off
+            if !limiter::should_retry(&resp, attempt, self.protocol.is_idempotent()) {
+                return Self::parse(resp).await;
             }
+            tokio::time::sleep(limiter::backoff(&resp, attempt)).await;
+            attempt += 1;
         }
     }
 
-    async fn send_post(self) -> Result<Response, SendRequestError> {
-        todo!()
+    // a token a header can't carry is refused without sending anything
+    fn build(&self) -> Result<reqwest::RequestBuilder, SendRequestError> {
+        let authorization = self
+            .authorization
+            .clone()
+            .ok_or_else(|| SendRequestError::Unauthorized(INVALID_TOKEN.to_string()))?;
+        let builder = match self.protocol {
+            RequestProtocol::GET => self.client.get(&self.url),
+            RequestProtocol::POST => self.client.post(&self.url),
+            RequestProtocol::PUT => self.client.put(&self.url),
+            RequestProtocol::DELETE => self.client.delete(&self.url),
+        };
+        Ok(builder.header(AUTHORIZATION, authorization))
+    }
+
+    async fn parse(resp: reqwest::Response) -> Result<Response, SendRequestError> {
+        let status = resp.status();
+        let body = resp.text().await?;
+        if status.is_success() {
+            let response: Response = serde_json::from_str(&body)?;
+            return Ok(response);
+        }
+        // error bodies are plain text, the message for the user
+        let message = body.trim().to_string();
+        Err(SendRequestError::from_status(status, message))
     }
 }
 
 impl Api {
-    pub fn new(api_key: String) -> Self {
+    pub fn new(
+        backend: Arc<dyn Backend>,
+        cache: Cache,
+        outbox: Outbox,
+        local_dir: String,
+    ) -> Self {
         Self {
-            key: api_key,
-            token: String::default(),
+            backend: backend.clone(),
+            remote: backend,
+            cache,
+            outbox,
+            local_dir,
         }
     }
 
     pub fn auth(&mut self, token: String) {
-        self.token = token;
+        self.remote.auth(token);
+        self.backend = self.remote.clone();
+    }
+
+    // serves the boards of a local pseudo-account from its file instead of the remote
+    pub fn use_local(&mut self, account: UserId) {
+        let path = PathBuf::from(format!("{}/{}.json", self.local_dir, account));
+        self.backend = Arc::new(Local::open(path, account));
+    }
+
+    pub fn backend(&self) -> Arc<dyn Backend> {
+        self.backend.clone()
+    }
+
+    // the account whose cache and outbox are in use
+    pub fn set_account(&mut self, account: Option<UserId>) {
+        self.cache.scope(account.clone());
+        self.outbox.scope(account);
+    }
+
+    pub fn cache(&self) -> Cache {
+        self.cache.clone()
+    }
+
+    pub fn outbox(&self) -> Outbox {
+        self.outbox.clone()
+    }
+}
+
+impl Trello {
+    pub fn new(api_key: String, endpoint: String, client: reqwest::Client) -> Self {
+        Self {
+            key: api_key,
+            token: RwLock::new(String::default()),
+            endpoint,
+            client,
+            limiter: Limiter::new(),
+        }
+    }
+
+    pub fn auth(&self, token: String) {
+        *self.token.write().unwrap() = token;
+    }
+
+    // the credentials travel in a header so they never end up in a url
+    fn authorization(&self) -> Option<HeaderValue> {
+        let value = format!(
+            "OAuth oauth_consumer_key=\"{}\", oauth_token=\"{}\"",
+            self.key,
+            self.token.read().unwrap()
+        );
+        let mut value = HeaderValue::from_str(&value).ok()?;
+        value.set_sensitive(true);
+        Some(value)
     }
 
     fn get_req<Response>(&self, url: String, fields: RequestFields) -> Request<Response>
@@ -99,14 +311,25 @@ impl Api {
         Response: serde::de::DeserializeOwned,
     {
         let fields = match fields {
-            RequestFields::All => "all".to_string(),
-            RequestFields::Raw(fields) => fields.to_string(),
             RequestFields::List(fields) => fields.join(","),
         };
-        let url = format!(
-            "{}/?key={}&token={}&fields={}",
-            url, self.key, self.token, fields
-        );
-        Request::get(url)
+        let url = format!("{}{}/?fields={}", self.endpoint, url, fields);
+        Request::get(url, self)
+    }
+
+    fn write_req<Response>(
+        &self,
+        url: String,
+        protocol: RequestProtocol,
+        params: Vec<(&str, String)>,
+    ) -> Request<Response>
+    where
+        Response: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", self.endpoint, url);
+        let url = reqwest::Url::parse_with_params(&url, params)
+            .map(|url| url.to_string())
+            .unwrap_or(url);
+        Request::new(url, protocol, self)
     }
 }
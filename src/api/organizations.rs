@@ -1,30 +1,40 @@
-use super::{Api, Request, RequestFields, ENDPOINT};
+use super::batch::{Batch, BatchEntry, BATCH_LIMIT};
+use super::{fields, Request, RequestFields, Trello};
 use crate::models::{Board, Organization, OrganizationId};
-use const_format::formatcp;
 
-const URL_BASE: &str = formatcp!("{}/organizations", ENDPOINT);
+const URL_BASE: &str = "/organizations";
 const URL_BOARDS: &str = "boards";
 
 pub trait Organizations {
-    fn organizations_get(&self, id: &OrganizationId) -> Request<Organization>;
+    fn organizations_batch(
+        &self,
+        ids: &[OrganizationId],
+    ) -> Vec<Request<Vec<BatchEntry<Organization>>>>;
 
     fn organizations_boards(&self, id: &OrganizationId) -> Request<Vec<Board>>;
 }
 
-impl Organizations for Api {
-    fn organizations_get(&self, id: &OrganizationId) -> Request<Organization> {
-        let fetch_user_url = format!("{}/{}", URL_BASE, id);
-        self.get_req(
-            fetch_user_url,
-            RequestFields::List(vec!["id", "name", "displayName", "desc", "url", "teamType"]),
-        )
+impl Organizations for Trello {
+    // one request per `BATCH_LIMIT` organizations instead of one each. The routes of a batch
+    // are comma separated, the commas between the fields are escaped so they stay in the route
+    fn organizations_batch(
+        &self,
+        ids: &[OrganizationId],
+    ) -> Vec<Request<Vec<BatchEntry<Organization>>>> {
+        let fields = fields::of::<Organization>().join("%2C");
+        ids.chunks(BATCH_LIMIT)
+            .map(|ids| {
+                let urls: Vec<String> = ids
+                    .iter()
+                    .map(|id| format!("{}/{}?fields={}", URL_BASE, id, fields))
+                    .collect();
+                self.batch(&urls)
+            })
+            .collect()
     }
 
     fn organizations_boards(&self, id: &OrganizationId) -> Request<Vec<Board>> {
         let fetch_user_url = format!("{}/{}/{}", URL_BASE, id, URL_BOARDS);
-        self.get_req(
-            fetch_user_url,
-            RequestFields::List(vec!["id", "name", "desc", "url", "pinned", "starred"]),
-        )
+        self.get_req(fetch_user_url, RequestFields::of::<Board>())
     }
 }
@@ -0,0 +1,31 @@
+use super::{Request, RequestProtocol, Trello};
+use crate::{
+    models::{Webhook, WebhookId},
+    APP_NAME,
+};
+
+const URL_BASE: &str = "/webhooks";
+
+pub trait Webhooks {
+    fn webhooks_create(&self, callback_url: &str, id_model: &str) -> Request<Webhook>;
+    fn webhooks_delete(&self, id: &WebhookId) -> Request<serde_json::Value>;
+}
+
+impl Webhooks for Trello {
+    fn webhooks_create(&self, callback_url: &str, id_model: &str) -> Request<Webhook> {
+        self.write_req(
+            URL_BASE.to_string(),
+            RequestProtocol::POST,
+            vec![
+                ("callbackURL", callback_url.to_string()),
+                ("idModel", id_model.to_string()),
+                ("description", APP_NAME.to_string()),
+            ],
+        )
+    }
+
+    fn webhooks_delete(&self, id: &WebhookId) -> Request<serde_json::Value> {
+        let delete_webhook_url = format!("{}/{}", URL_BASE, id);
+        self.write_req(delete_webhook_url, RequestProtocol::DELETE, vec![])
+    }
+}
@@ -0,0 +1,321 @@
+use super::{timestamp, Backend, Pending};
+use crate::api::SendRequestError;
+use crate::database::{LOCAL_ACCOUNT, LOCAL_USERNAME};
+use crate::models::{
+    Board, BoardContent, BoardId, Card, CardId, Label, List, ListId, Organization, OrganizationId,
+    User, UserId, Webhook, WebhookId,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fs, future,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+// every local board lives in this one workspace, named after the account it belongs to
+const ORGANIZATION_ID: &str = LOCAL_ACCOUNT;
+const ORGANIZATION_NAME: &str = "local";
+const DEFAULT_BOARD: &str = "Personal";
+const DEFAULT_LISTS: [&str; 3] = ["To do", "Doing", "Done"];
+
+#[derive(Deserialize, Serialize)]
+struct Comment {
+    id_card: CardId,
+    text: String,
+    date: String,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct Store {
+    boards: Vec<Board>,
+    lists: Vec<List>,
+    cards: Vec<Card>,
+    #[serde(default)]
+    comments: Vec<Comment>,
+    next_id: u64,
+}
+
+impl Store {
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("{:024x}", self.next_id)
+    }
+
+    // a fresh account starts out with a board to put cards on
+    fn seeded() -> Self {
+        let mut store = Store::default();
+        let id_board = BoardId::unchecked(store.new_id());
+        store.boards.push(Board {
+            id: id_board.clone(),
+            name: DEFAULT_BOARD.to_string(),
+            desc: String::new(),
+            url: String::new(),
+            pinned: false,
+            starred: false,
+            date_last_activity: Some(timestamp()),
+        });
+        for name in DEFAULT_LISTS {
+            let id = ListId::unchecked(store.new_id());
+            store.lists.push(List {
+                id,
+                id_board: id_board.clone(),
+                id_organization: Some(OrganizationId::unchecked(ORGANIZATION_ID)),
+                name: name.to_string(),
+            });
+        }
+        store
+    }
+
+    fn touch(&mut self, id_board: &BoardId, now: &str) {
+        if let Some(board) = self.boards.iter_mut().find(|board| board.id == *id_board) {
+            board.date_last_activity = Some(now.to_string());
+        }
+    }
+}
+
+fn organization() -> Organization {
+    Organization {
+        id: OrganizationId::unchecked(ORGANIZATION_ID),
+        name: ORGANIZATION_NAME.to_string(),
+        display_name: "Local boards".to_string(),
+        desc: String::new(),
+        url: String::new(),
+        team_type: String::new(),
+    }
+}
+
+fn not_found<T>() -> Result<T, SendRequestError> {
+    Err(SendRequestError::NotFound(
+        "The requested resource was not found.".to_string(),
+    ))
+}
+
+// Boards that never leave the machine, kept in a JSON file per pseudo-account.
+// Lists and boards beyond the seeded ones can be added by editing that file.
+pub struct Local {
+    path: PathBuf,
+    account: UserId,
+    store: Mutex<Store>,
+    // why the file can't be written over, when it couldn't be read nor set aside
+    read_only: Option<String>,
+}
+
+// Only a missing file makes for a fresh account. One that can't be read or parsed is moved
+// to `.bak` before starting over, if even that fails it's left alone and nothing gets saved.
+fn load(path: &Path) -> (Store, Option<String>) {
+    let err = match fs::read_to_string(path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => return (Store::seeded(), None),
+        Err(err) => err.to_string(),
+        Ok(raw) => match serde_json::from_str(&raw) {
+            Ok(store) => return (store, None),
+            Err(err) => err.to_string(),
+        },
+    };
+    let backup = path.with_extension("json.bak");
+    match fs::rename(path, &backup) {
+        Ok(_) => (Store::seeded(), None),
+        Err(_) => (
+            Store::seeded(),
+            Some(format!("Can't read {}: {}", path.display(), err)),
+        ),
+    }
+}
+
+impl Local {
+    pub fn open(path: PathBuf, account: UserId) -> Self {
+        let (store, read_only) = load(&path);
+        Self {
+            path,
+            account,
+            store: Mutex::new(store),
+            read_only,
+        }
+    }
+
+    fn read<Response, F>(&self, f: F) -> Pending<Response>
+    where
+        Response: Send + 'static,
+        F: FnOnce(&Store) -> Result<Response, SendRequestError>,
+    {
+        Box::pin(future::ready(f(&self.store.lock().unwrap())))
+    }
+
+    // changes get written out right away, through a temporary file so a crash can't truncate it
+    fn write<Response, F>(&self, f: F) -> Pending<Response>
+    where
+        Response: Send + 'static,
+        F: FnOnce(&mut Store, &str) -> Result<Response, SendRequestError>,
+    {
+        if let Some(reason) = &self.read_only {
+            let err = SendRequestError::StatusError(500, reason.clone());
+            return Box::pin(future::ready(Err(err)));
+        }
+        let mut store = self.store.lock().unwrap();
+        let result = f(&mut store, &timestamp()).and_then(|response| {
+            let content = serde_json::to_string_pretty(&*store)?;
+            let tmp = self.path.with_extension("json.tmp");
+            self.path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&tmp, content))
+                .and_then(|_| fs::rename(&tmp, &self.path))
+                .map_err(|err| SendRequestError::StatusError(500, err.to_string()))?;
+            Ok(response)
+        });
+        Box::pin(future::ready(result))
+    }
+}
+
+impl Backend for Local {
+    // nothing to authenticate against
+    fn auth(&self, _token: String) {}
+
+    fn members_me(&self) -> Pending<User> {
+        let account = self.account.clone();
+        self.read(|store| {
+            Ok(User {
+                username: LOCAL_USERNAME.to_string(),
+                id: account,
+                id_boards: store.boards.iter().map(|board| board.id.clone()).collect(),
+                id_organizations: vec![OrganizationId::unchecked(ORGANIZATION_ID)],
+            })
+        })
+    }
+
+    fn organizations_batch(&self, ids: &[OrganizationId]) -> Pending<Vec<Organization>> {
+        let organizations = ids
+            .iter()
+            .filter(|id| *id == ORGANIZATION_ID)
+            .map(|_| organization())
+            .collect();
+        Box::pin(future::ready(Ok(organizations)))
+    }
+
+    fn organizations_boards(&self, id: &OrganizationId) -> Pending<Vec<Board>> {
+        let id = id.clone();
+        self.read(move |store| match id.as_str() {
+            ORGANIZATION_ID => Ok(store.boards.clone()),
+            _ => not_found(),
+        })
+    }
+
+    fn boards_get(&self, id: &BoardId) -> Pending<Board> {
+        self.read(
+            |store| match store.boards.iter().find(|board| board.id == *id) {
+                Some(board) => Ok(board.clone()),
+                None => not_found(),
+            },
+        )
+    }
+
+    fn boards_content(&self, id: &BoardId) -> Pending<BoardContent> {
+        self.read(|store| {
+            let board = match store.boards.iter().find(|board| board.id == *id) {
+                Some(board) => board.clone(),
+                None => return not_found(),
+            };
+            let lists = store.lists.iter().filter(|list| list.id_board == *id);
+            let cards: Vec<Card> = store
+                .cards
+                .iter()
+                .filter(|card| card.id_board == *id)
+                .cloned()
+                .collect();
+            let mut labels: Vec<Label> = Vec::new();
+            for label in cards.iter().flat_map(|card| &card.labels) {
+                if !labels.iter().any(|known| known.id == label.id) {
+                    labels.push(label.clone());
+                }
+            }
+            Ok(BoardContent {
+                board,
+                lists: lists.cloned().collect(),
+                cards,
+                labels,
+            })
+        })
+    }
+
+    fn cards_get(&self, id: &CardId) -> Pending<Card> {
+        self.read(
+            |store| match store.cards.iter().find(|card| card.id == *id) {
+                Some(card) => Ok(card.clone()),
+                None => not_found(),
+            },
+        )
+    }
+
+    fn cards_create(&self, id_list: &ListId, name: &str) -> Pending<Card> {
+        self.write(|store, now| {
+            let list = match store.lists.iter().find(|list| list.id == *id_list) {
+                Some(list) => list.clone(),
+                None => return not_found(),
+            };
+            let card = Card {
+                id: CardId::unchecked(store.new_id()),
+                id_board: list.id_board.clone(),
+                id_list: list.id,
+                id_organization: list.id_organization,
+                name: name.to_string(),
+                labels: Vec::new(),
+                url: String::new(),
+                date_last_activity: Some(now.to_string()),
+            };
+            store.touch(&list.id_board, now);
+            store.cards.push(card.clone());
+            Ok(card)
+        })
+    }
+
+    fn cards_update(
+        &self,
+        id: &CardId,
+        name: Option<&String>,
+        id_list: Option<&ListId>,
+    ) -> Pending<Card> {
+        self.write(|store, now| {
+            let card = match store.cards.iter_mut().find(|card| card.id == *id) {
+                Some(card) => card,
+                None => return not_found(),
+            };
+            if let Some(name) = name {
+                card.name = name.clone();
+            }
+            if let Some(id_list) = id_list {
+                card.id_list = id_list.clone();
+            }
+            card.date_last_activity = Some(now.to_string());
+            let card = card.clone();
+            store.touch(&card.id_board, now);
+            Ok(card)
+        })
+    }
+
+    fn cards_comment(&self, id: &CardId, text: &str) -> Pending<serde_json::Value> {
+        self.write(|store, now| {
+            let id_board = match store.cards.iter().find(|card| card.id == *id) {
+                Some(card) => card.id_board.clone(),
+                None => return not_found(),
+            };
+            store.comments.push(Comment {
+                id_card: id.clone(),
+                text: text.to_string(),
+                date: now.to_string(),
+            });
+            store.touch(&id_board, now);
+            Ok(json!({ "type": "commentCard", "date": now, "data": { "text": text } }))
+        })
+    }
+
+    fn webhooks_create(&self, _callback_url: &str, _id_model: &str) -> Pending<Webhook> {
+        Box::pin(future::ready(Err(SendRequestError::Forbidden(
+            "Local boards have no webhooks".to_string(),
+        ))))
+    }
+
+    fn webhooks_delete(&self, _id: &WebhookId) -> Pending<serde_json::Value> {
+        Box::pin(future::ready(Ok(json!({}))))
+    }
+}
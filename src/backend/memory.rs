@@ -0,0 +1,147 @@
+use super::{Backend, Pending};
+use crate::api::batch::BatchEntry;
+use crate::api::SendRequestError;
+use crate::mock::state::{self, State};
+use crate::models::{
+    Board, BoardContent, BoardId, Card, CardId, ListId, Organization, OrganizationId, User,
+    Webhook, WebhookId,
+};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    future,
+    sync::{Arc, Mutex, RwLock},
+};
+
+// Serves the mock fixtures straight from memory, no sockets involved.
+// Changes are kept for as long as it lives.
+pub struct Memory {
+    state: Arc<Mutex<State>>,
+    token: RwLock<String>,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State::new())),
+            token: RwLock::new(String::default()),
+        }
+    }
+
+    fn call<Response>(
+        &self,
+        method: &str,
+        path: &[&str],
+        query: &[(&str, &str)],
+    ) -> Pending<Response>
+    where
+        Response: DeserializeOwned + Send + 'static,
+    {
+        let query: HashMap<String, String> = query
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let (status, body) = match state::check_token(&self.token.read().unwrap()) {
+            Ok(_) => state::route(&mut self.state.lock().unwrap(), method, path, &query),
+            Err(response) => response,
+        };
+        Box::pin(future::ready(parse(status, body)))
+    }
+}
+
+// the same answers the http client would give for the same status
+fn parse<Response>(status: u16, body: Value) -> Result<Response, SendRequestError>
+where
+    Response: DeserializeOwned,
+{
+    let message = match body {
+        body if status == 200 => return Ok(serde_json::from_value(body)?),
+        Value::String(message) => message,
+        body => body.to_string(),
+    };
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    Err(SendRequestError::from_status(status, message))
+}
+
+impl Backend for Memory {
+    fn auth(&self, token: String) {
+        *self.token.write().unwrap() = token;
+    }
+
+    fn members_me(&self) -> Pending<User> {
+        self.call("GET", &["members", "me"], &[])
+    }
+
+    fn organizations_batch(&self, ids: &[OrganizationId]) -> Pending<Vec<Organization>> {
+        let urls: Vec<String> = ids
+            .iter()
+            .map(|id| format!("/organizations/{}", id))
+            .collect();
+        let batch: Pending<Vec<BatchEntry<Organization>>> =
+            self.call("GET", &["batch"], &[("urls", &urls.join(","))]);
+        Box::pin(async move {
+            let entries = batch.await?;
+            Ok(entries.into_iter().filter_map(|entry| entry.ok).collect())
+        })
+    }
+
+    fn organizations_boards(&self, id: &OrganizationId) -> Pending<Vec<Board>> {
+        self.call("GET", &["organizations", id.as_str(), "boards"], &[])
+    }
+
+    fn boards_get(&self, id: &BoardId) -> Pending<Board> {
+        self.call("GET", &["boards", id.as_str()], &[])
+    }
+
+    fn boards_content(&self, id: &BoardId) -> Pending<BoardContent> {
+        let query = [("lists", "open"), ("cards", "open"), ("labels", "all")];
+        self.call("GET", &["boards", id.as_str()], &query)
+    }
+
+    fn cards_get(&self, id: &CardId) -> Pending<Card> {
+        self.call("GET", &["cards", id.as_str()], &[])
+    }
+
+    fn cards_create(&self, id_list: &ListId, name: &str) -> Pending<Card> {
+        self.call(
+            "POST",
+            &["cards"],
+            &[("idList", id_list.as_str()), ("name", name)],
+        )
+    }
+
+    fn cards_update(
+        &self,
+        id: &CardId,
+        name: Option<&String>,
+        id_list: Option<&ListId>,
+    ) -> Pending<Card> {
+        let query: Vec<(&str, &str)> = [
+            ("name", name.map(String::as_str)),
+            ("idList", id_list.map(ListId::as_str)),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|v| (key, v)))
+        .collect();
+        self.call("PUT", &["cards", id.as_str()], &query)
+    }
+
+    fn cards_comment(&self, id: &CardId, text: &str) -> Pending<serde_json::Value> {
+        self.call(
+            "POST",
+            &["cards", id.as_str(), "actions", "comments"],
+            &[("text", text)],
+        )
+    }
+
+    fn webhooks_create(&self, callback_url: &str, id_model: &str) -> Pending<Webhook> {
+        let query = [("callbackURL", callback_url), ("idModel", id_model)];
+        self.call("POST", &["webhooks"], &query)
+    }
+
+    fn webhooks_delete(&self, id: &WebhookId) -> Pending<serde_json::Value> {
+        self.call("DELETE", &["webhooks", id.as_str()], &[])
+    }
+}
@@ -0,0 +1,120 @@
+mod local;
+pub mod memory;
+mod trello;
+
+use crate::api::SendRequestError;
+use crate::models::{
+    Board, BoardContent, BoardId, Card, CardId, ListId, Organization, OrganizationId, User,
+    Webhook, WebhookId,
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub use local::Local;
+pub use memory::Memory;
+
+// the work behind a call, it doesn't borrow the backend so it can outlive the api lock
+pub type Pending<Response> =
+    Pin<Box<dyn Future<Output = Result<Response, SendRequestError>> + Send>>;
+
+// Wherever the boards come from. The pages only ever talk to this, Trello's API being
+// one implementation and `Memory` another.
+pub trait Backend: Send + Sync {
+    fn auth(&self, token: String);
+
+    fn members_me(&self) -> Pending<User>;
+
+    // the ones that can't be found are left out
+    fn organizations_batch(&self, ids: &[OrganizationId]) -> Pending<Vec<Organization>>;
+    fn organizations_boards(&self, id: &OrganizationId) -> Pending<Vec<Board>>;
+
+    fn boards_get(&self, id: &BoardId) -> Pending<Board>;
+    fn boards_content(&self, id: &BoardId) -> Pending<BoardContent>;
+
+    fn cards_get(&self, id: &CardId) -> Pending<Card>;
+    fn cards_create(&self, id_list: &ListId, name: &str) -> Pending<Card>;
+    fn cards_update(
+        &self,
+        id: &CardId,
+        name: Option<&String>,
+        id_list: Option<&ListId>,
+    ) -> Pending<Card>;
+    fn cards_comment(&self, id: &CardId, text: &str) -> Pending<serde_json::Value>;
+
+    fn webhooks_create(&self, callback_url: &str, id_model: &str) -> Pending<Webhook>;
+    fn webhooks_delete(&self, id: &WebhookId) -> Pending<serde_json::Value>;
+}
+
+// Trello's format, 2024-03-12T10:15:30.000Z
+pub fn timestamp() -> String {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format_timestamp(elapsed)
+}
+
+fn format_timestamp(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+    // days to a civil date, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> String {
+        format_timestamp(Duration::from_secs(secs))
+    }
+
+    #[test]
+    fn epoch() {
+        assert_eq!(at(0), "1970-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn millis() {
+        let elapsed = Duration::from_millis(1_710_238_530_042);
+        assert_eq!(format_timestamp(elapsed), "2024-03-12T10:15:30.042Z");
+    }
+
+    #[test]
+    fn leap_days() {
+        assert_eq!(at(951_782_400), "2000-02-29T00:00:00.000Z");
+        assert_eq!(at(1_709_164_800), "2024-02-29T00:00:00.000Z");
+        assert_eq!(at(1_709_251_199), "2024-02-29T23:59:59.000Z");
+        // 2100 is divisible by 100 but not 400, no 29th
+        assert_eq!(at(4_107_542_399), "2100-02-28T23:59:59.000Z");
+        assert_eq!(at(4_107_542_400), "2100-03-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn year_boundaries() {
+        assert_eq!(at(946_684_799), "1999-12-31T23:59:59.000Z");
+        assert_eq!(at(946_684_800), "2000-01-01T00:00:00.000Z");
+        assert_eq!(at(1_704_067_199), "2023-12-31T23:59:59.000Z");
+        assert_eq!(at(1_704_067_200), "2024-01-01T00:00:00.000Z");
+    }
+}
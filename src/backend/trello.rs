@@ -0,0 +1,82 @@
+use super::{Backend, Pending};
+use crate::api::{
+    boards::{self, Boards},
+    cards::Cards,
+    members::Members,
+    organizations::Organizations,
+    webhooks::Webhooks,
+    Trello,
+};
+use crate::models::{
+    Board, BoardContent, BoardId, Card, CardId, ListId, Organization, OrganizationId, User,
+    Webhook, WebhookId,
+};
+
+impl Backend for Trello {
+    fn auth(&self, token: String) {
+        Trello::auth(self, token);
+    }
+
+    fn members_me(&self) -> Pending<User> {
+        Box::pin(Members::members_me(self).send())
+    }
+
+    fn organizations_batch(&self, ids: &[OrganizationId]) -> Pending<Vec<Organization>> {
+        let batches = Organizations::organizations_batch(self, ids);
+        Box::pin(async move {
+            let mut organizations = Vec::new();
+            for batch in batches {
+                let entries = batch.send().await?;
+                organizations.extend(entries.into_iter().filter_map(|entry| entry.ok));
+            }
+            Ok(organizations)
+        })
+    }
+
+    fn organizations_boards(&self, id: &OrganizationId) -> Pending<Vec<Board>> {
+        Box::pin(Organizations::organizations_boards(self, id).send())
+    }
+
+    fn boards_get(&self, id: &BoardId) -> Pending<Board> {
+        Box::pin(Boards::boards_get(self, id).send())
+    }
+
+    fn boards_content(&self, id: &BoardId) -> Pending<BoardContent> {
+        let content = Boards::boards_content(self, id);
+        let page = Boards::boards_cards(self, id);
+        Box::pin(async move {
+            let mut content = content.send().await?;
+            content.cards = boards::page_cards(content.cards, page).await?;
+            Ok(content)
+        })
+    }
+
+    fn cards_get(&self, id: &CardId) -> Pending<Card> {
+        Box::pin(Cards::cards_get(self, id).send())
+    }
+
+    fn cards_create(&self, id_list: &ListId, name: &str) -> Pending<Card> {
+        Box::pin(Cards::cards_create(self, id_list, name).send())
+    }
+
+    fn cards_update(
+        &self,
+        id: &CardId,
+        name: Option<&String>,
+        id_list: Option<&ListId>,
+    ) -> Pending<Card> {
+        Box::pin(Cards::cards_update(self, id, name, id_list).send())
+    }
+
+    fn cards_comment(&self, id: &CardId, text: &str) -> Pending<serde_json::Value> {
+        Box::pin(Cards::cards_comment(self, id, text).send())
+    }
+
+    fn webhooks_create(&self, callback_url: &str, id_model: &str) -> Pending<Webhook> {
+        Box::pin(Webhooks::webhooks_create(self, callback_url, id_model).send())
+    }
+
+    fn webhooks_delete(&self, id: &WebhookId) -> Pending<serde_json::Value> {
+        Box::pin(Webhooks::webhooks_delete(self, id).send())
+    }
+}
@@ -0,0 +1,12 @@
+use crate::models::{BoardId, OrganizationId};
+
+pub const MEMBERS_ME: &str = "members/me";
+pub const ORGANIZATIONS: &str = "members/me/organizations";
+
+pub fn board(id: &BoardId) -> String {
+    format!("boards/{}", id)
+}
+
+pub fn organization_boards(id: &OrganizationId) -> String {
+    format!("organizations/{}/boards", id)
+}
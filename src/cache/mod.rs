@@ -0,0 +1,182 @@
+pub mod keys;
+
+use crate::backend::Pending;
+use crate::input::{Event, EventSender};
+use crate::models::UserId;
+use crate::Ignore;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+#[derive(Deserialize, Serialize)]
+struct Entry<T> {
+    fetched_at: SystemTime,
+    value: T,
+}
+
+#[derive(Default)]
+struct State {
+    account: Option<UserId>,
+    // when the cached entries handed out since their last refresh got fetched, by key
+    stale: HashMap<String, SystemTime>,
+    offline: bool,
+}
+
+pub struct CacheStatus {
+    pub age: Duration,
+    pub offline: bool,
+}
+
+// Local copy of the API responses, kept per account so pages can render before the network
+// answers and stay browsable while offline. Cheap to clone, all clones share the same state.
+#[derive(Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    state: Arc<Mutex<State>>,
+}
+
+impl Cache {
+    pub fn new(dir: String) -> Self {
+        Self {
+            dir: PathBuf::from(dir),
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    // every account gets a cache of its own, without one nothing is read or written
+    pub fn scope(&self, account: Option<UserId>) {
+        let mut state = self.state.lock().unwrap();
+        state.account = account;
+        state.stale.clear();
+    }
+
+    // how old the oldest stale entry is, nothing once every entry handed out got refreshed
+    pub fn status(&self) -> Option<CacheStatus> {
+        let state = self.state.lock().unwrap();
+        state.stale.values().min().map(|fetched_at| CacheStatus {
+            age: fetched_at.elapsed().unwrap_or_default(),
+            offline: state.offline,
+        })
+    }
+
+    fn entry_path(&self, key: &str) -> Option<PathBuf> {
+        let state = self.state.lock().unwrap();
+        state.account.as_ref().map(|account| {
+            self.dir
+                .join(account.as_str())
+                .join(format!("{}.json", key.replace('/', "_")))
+        })
+    }
+
+    fn read<T>(&self, key: &str) -> Option<Entry<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let raw = fs::read_to_string(self.entry_path(key)?).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.read(key).map(|entry| entry.value)
+    }
+
+    pub fn put<T>(&self, key: &str, value: &T)
+    where
+        T: Serialize,
+    {
+        if let Some(path) = self.entry_path(key) {
+            let entry = Entry {
+                fetched_at: SystemTime::now(),
+                value,
+            };
+            if let (Some(dir), Ok(content)) = (path.parent(), serde_json::to_string(&entry)) {
+                fs::create_dir_all(dir)
+                    .and_then(|_| fs::write(&path, content))
+                    .ignore();
+            }
+        }
+    }
+
+    fn served(&self, key: &str, fetched_at: SystemTime) {
+        let mut state = self.state.lock().unwrap();
+        state.stale.insert(key.to_string(), fetched_at);
+    }
+
+    // only the refreshed entry is fresh again, the others stay as old as they were
+    fn refreshed(&self, key: &str, online: bool) {
+        let mut state = self.state.lock().unwrap();
+        if online {
+            state.stale.remove(key);
+        }
+        state.offline = !online;
+    }
+
+    // fetches a fresh copy into the cache and lets the pages know about it
+    pub async fn refresh<Response>(
+        &self,
+        key: String,
+        req: Pending<Response>,
+        event_sender: &EventSender,
+    ) where
+        Response: DeserializeOwned + Serialize,
+    {
+        match req.await {
+            Ok(response) => {
+                self.put(&key, &response);
+                self.refreshed(&key, true);
+                event_sender.send(Event::CacheRefreshed(key)).ignore();
+            }
+            Err(err) if err.is_transient() => self.refreshed(&key, false),
+            Err(_) => {}
+        }
+    }
+
+    // Never waits on the backend. Hands out the cached copy if there is one and refreshes it
+    // in the background, either way the page gets an `Event::CacheRefreshed(key)` once the
+    // fresh copy is in. Without a cached copy a failure comes as `Event::CacheFailed`.
+    pub fn load<Response>(
+        &self,
+        key: String,
+        req: Pending<Response>,
+        event_sender: &EventSender,
+    ) -> Option<Response>
+    where
+        Response: DeserializeOwned + Serialize + Send + 'static,
+    {
+        let cached = self.read::<Response>(&key).map(|entry| {
+            self.served(&key, entry.fetched_at);
+            entry.value
+        });
+        tokio::spawn({
+            let cache = self.clone();
+            let event_sender = event_sender.clone();
+            let report = cached.is_none();
+            async move {
+                match req.await {
+                    Ok(response) => {
+                        cache.put(&key, &response);
+                        cache.refreshed(&key, true);
+                        event_sender.send(Event::CacheRefreshed(key)).ignore();
+                    }
+                    Err(err) => {
+                        if err.is_transient() {
+                            cache.refreshed(&key, false);
+                        }
+                        if report {
+                            event_sender.send(Event::CacheFailed(key, err)).ignore();
+                        }
+                    }
+                }
+            }
+        });
+        cached
+    }
+}
@@ -1,9 +1,35 @@
 use super::UserId;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+// where the boards of an account come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountKind {
+    #[default]
+    Trello,
+    // a pseudo-account whose boards are kept in a file under the data dir
+    Local,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub struct Account {
     pub id: UserId,
     pub username: String,
+    #[serde(default)]
+    pub kind: AccountKind,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub token: String,
 }
+
+// the token stays out of any debug output
+impl fmt::Debug for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Account")
+            .field("id", &self.id)
+            .field("username", &self.username)
+            .field("kind", &self.kind)
+            .field("token", &"[redacted]")
+            .finish()
+    }
+}
@@ -0,0 +1,35 @@
+use super::DatabaseError;
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    thread,
+    time::Duration,
+};
+
+const RETRY_DELAY: Duration = Duration::from_millis(50);
+const RETRY_COUNT: u32 = 40;
+
+// Advisory lock that serializes the read-merge-write cycle between running instances. The
+// operating system lets go of it together with the file, so it's released as soon as it gets
+// dropped and a crashed instance can't leave it behind. The file itself stays around, removing
+// it would let two instances lock two different files under the same name.
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    pub fn acquire(db_path: &str) -> Result<Self, DatabaseError> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(format!("{}.lock", db_path))?;
+        for _ in 0..RETRY_COUNT {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(TryLockError::WouldBlock) => thread::sleep(RETRY_DELAY),
+                Err(TryLockError::Error(err)) => return Err(err.into()),
+            }
+        }
+        Err(DatabaseError::Locked)
+    }
+}
@@ -0,0 +1,50 @@
+use super::DatabaseError;
+use toml::{Table, Value};
+
+pub const CURRENT_VERSION: u32 = 1;
+
+const VERSION_KEY: &str = "version";
+
+type Migration = fn(&mut Table);
+
+// MIGRATIONS[n] upgrades a database from version `n` to `n + 1`
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [v0_to_v1];
+
+pub fn version_of(value: &Value) -> u32 {
+    // databases written before versioning was introduced have no version field
+    value
+        .get(VERSION_KEY)
+        .and_then(|v| v.as_integer())
+        .map_or(0, |v| v as u32)
+}
+
+// upgrades the raw database to the current layout, returns the version it started from
+pub fn migrate(value: &mut Value) -> Result<u32, DatabaseError> {
+    let from = version_of(value);
+    if from > CURRENT_VERSION {
+        return Err(DatabaseError::UnsupportedVersion(from));
+    }
+    let table = value
+        .as_table_mut()
+        .ok_or(DatabaseError::UnsupportedVersion(from))?;
+    MIGRATIONS[from as usize..]
+        .iter()
+        .enumerate()
+        .for_each(|(index, migration)| {
+            migration(table);
+            table.insert(
+                VERSION_KEY.to_string(),
+                Value::Integer((from as usize + index + 1) as i64),
+            );
+        });
+    Ok(from)
+}
+
+// v1 introduced the secret backends, older databases always kept the tokens in the file
+fn v0_to_v1(table: &mut Table) {
+    if !table.contains_key("secret_backend") {
+        let mut backend = Table::new();
+        backend.insert("kind".to_string(), Value::String("file".to_string()));
+        table.insert("secret_backend".to_string(), Value::Table(backend));
+    }
+}
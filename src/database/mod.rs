@@ -1,81 +1,437 @@
 mod account;
+mod lock;
+mod migrations;
+mod network;
+mod secret_store;
+mod webhook;
 
 use crate::models::{User, UserId};
-use account::Account;
+use crate::{Ignore, APP_NAME};
+pub use account::{Account, AccountKind};
+use lock::FileLock;
+pub use network::NetworkConfig;
+use secret_store::SecretBackend;
+pub use webhook::WebhookConfig;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, io::Error as IoError, path::Path};
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{Error as IoError, ErrorKind, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use thiserror::Error;
 use toml;
 
-#[derive(Debug)]
-pub enum DatabaseError {
-    IoError(IoError),
-    SerializationError(toml::ser::Error),
-    DeserializationError(toml::de::Error),
-    KeyNotFound,
-}
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
 
-impl From<IoError> for DatabaseError {
-    fn from(err: IoError) -> Self {
-        Self::IoError(err)
-    }
-}
+// how long the database has to stay untouched before a pending change gets flushed
+const FLUSH_DEBOUNCE: Duration = Duration::from_millis(500);
+const FLUSH_POLL_RATE: Duration = Duration::from_millis(250);
 
-impl From<toml::ser::Error> for DatabaseError {
-    fn from(err: toml::ser::Error) -> Self {
-        Self::SerializationError(err)
-    }
+// id of the pseudo-account whose boards are kept on the disk, shaped like a Trello one so it
+// gets through the same validation
+pub const LOCAL_ACCOUNT: &str = "000000000000000000000000";
+pub const LOCAL_USERNAME: &str = "local";
+
+// only the owner should be able to read the tokens
+#[cfg(unix)]
+const FILE_MODE: u32 = 0o600;
+
+#[derive(Error, Debug)]
+pub enum DatabaseError {
+    #[error("Failed to access the database file: {0}")]
+    IoError(#[from] IoError),
+    #[error("Failed to serialize the database: {0}")]
+    SerializationError(#[from] toml::ser::Error),
+    #[error("The database file is corrupted: {0}")]
+    DeserializationError(#[from] toml::de::Error),
+    #[error("Secret backend failed: {0}")]
+    SecretStoreError(String),
+    #[error("The database was written by a newer version (v{0}) of {APP_NAME}")]
+    UnsupportedVersion(u32),
+    #[error("The database is locked by another instance of {APP_NAME}")]
+    Locked,
+    #[error("Key not found")]
+    KeyNotFound,
 }
 
-impl From<toml::de::Error> for DatabaseError {
-    fn from(err: toml::de::Error) -> Self {
-        Self::DeserializationError(err)
+impl DatabaseError {
+    // a file from a newer version or one that can't be read right now is still worth keeping
+    pub fn is_corruption(&self) -> bool {
+        matches!(self, Self::DeserializationError(_))
     }
 }
 
 #[derive(Deserialize, Serialize)]
 pub struct Database {
+    pub version: u32,
     pub first_load: bool,
     pub active_account: Option<UserId>,
     pub accounts: HashMap<UserId, Account>,
+    #[serde(default)]
+    pub secret_backend: SecretBackend,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkConfig>,
     path: String,
+    #[serde(skip)]
+    read_only: bool,
+    #[serde(skip)]
+    load_error: Option<String>,
+    #[serde(skip)]
+    corrupt: bool,
+    #[serde(skip)]
+    synced: Synced,
+    #[serde(skip)]
+    changed_at: Option<Instant>,
+    // the tokens as the secret backend has them, so only the changed ones get stored again
+    #[serde(skip)]
+    stored_secrets: HashMap<UserId, String>,
+    // shown in the status bar, the terminal belongs to the ui by the time these come up
+    #[serde(skip)]
+    permission_warning: Option<String>,
+    #[serde(skip)]
+    secret_error: Option<String>,
+    #[serde(skip)]
+    save_error: Option<String>,
+}
+
+// what the file contained the last time this instance read or wrote it,
+// used to tell our own changes apart from the ones made by other instances
+#[derive(Default)]
+struct Synced {
+    active_account: Option<UserId>,
+    accounts: HashMap<UserId, Account>,
 }
 
 impl Database {
     pub fn new(path: String) -> Self {
         Database {
+            version: migrations::CURRENT_VERSION,
             first_load: true,
             active_account: None,
             accounts: HashMap::new(),
+            secret_backend: SecretBackend::default(),
+            webhook: None,
+            network: None,
             path,
+            read_only: false,
+            load_error: None,
+            corrupt: false,
+            synced: Synced::default(),
+            changed_at: None,
+            stored_secrets: HashMap::new(),
+            permission_warning: None,
+            secret_error: None,
+            save_error: None,
         }
     }
-    pub fn load(path: &str) -> Self {
+    // an empty database that stands in for the one at `path` which failed to load,
+    // it never touches the file until it gets reset
+    pub fn unreadable(path: String, err: &DatabaseError) -> Self {
+        let mut db = Database::new(path);
+        db.read_only = true;
+        db.load_error = Some(err.to_string());
+        db.corrupt = err.is_corruption();
+        db
+    }
+
+    // a throwaway database for the demo mode, nothing of it gets written to the disk
+    pub fn demo() -> Self {
+        let mut db = Database::new(String::new());
+        db.read_only = true;
+        db.first_load = false;
+        db
+    }
+
+    pub fn load(path: &str) -> Result<Self, DatabaseError> {
         let p = Path::new(path);
         if p.exists() {
-            let raw = fs::read_to_string(p).unwrap();
-            return toml::from_str(&raw).unwrap();
+            let raw = fs::read_to_string(p)?;
+            let (mut db, from) = Database::parse(&raw)?;
+            db.permission_warning = loose_permissions(p);
+            if from != migrations::CURRENT_VERSION {
+                let _lock = FileLock::acquire(path)?;
+                // keep the old layout around in case the migration went wrong
+                fs::copy(path, format!("{}.v{}.bak", path, from))?;
+                write_atomic(path, toml::to_string(&db)?.as_bytes())?;
+            }
+            db.load_secrets();
+            db.mark_synced();
+            Ok(db)
+        } else {
+            Ok(Database::new(path.to_string()))
+        }
+    }
+
+    // returns the database along with the version it was migrated from
+    fn parse(raw: &str) -> Result<(Self, u32), DatabaseError> {
+        let mut value: toml::Value = toml::from_str(raw)?;
+        let from = migrations::migrate(&mut value)?;
+        Ok((value.try_into()?, from))
+    }
+
+    fn mark_synced(&mut self) {
+        self.synced = Synced {
+            active_account: self.active_account.clone(),
+            accounts: self.accounts.clone(),
+        };
+    }
+
+    // applies the changes made by this instance on top of whatever is currently on the disk
+    fn merge(&mut self, disk: Database) {
+        let mut accounts = disk.accounts;
+        self.synced
+            .accounts
+            .keys()
+            .filter(|id| !self.accounts.contains_key(*id))
+            .for_each(|removed| {
+                accounts.remove(removed);
+            });
+        self.accounts
+            .iter()
+            .filter(|(id, account)| self.synced.accounts.get(*id) != Some(account))
+            .for_each(|(id, changed)| {
+                accounts.insert(id.clone(), changed.clone());
+            });
+        // tokens that live in a secret backend aren't part of the file
+        accounts
+            .values_mut()
+            .filter(|account| account.token.is_empty())
+            .for_each(|account| {
+                if let Some(known) = self.accounts.get(&account.id) {
+                    account.token = known.token.clone();
+                }
+            });
+
+        let active_account = if self.active_account != self.synced.active_account {
+            self.active_account.take()
         } else {
-            return Database::new(path.to_string());
+            disk.active_account
+        };
+        self.active_account = active_account.filter(|id| accounts.contains_key(id));
+        self.accounts = accounts;
+        self.first_load = self.first_load && disk.first_load;
+        // only ever edited by hand
+        self.webhook = disk.webhook;
+        self.network = disk.network;
+        self.load_secrets();
+    }
+
+    pub fn path(&self) -> &String {
+        &self.path
+    }
+
+    // the reason this database couldn't be loaded, if any
+    pub fn load_error(&self) -> Option<&String> {
+        self.load_error.as_ref()
+    }
+
+    // something the user should know about that doesn't stop the database from working
+    pub fn warning(&self) -> Option<&String> {
+        self.save_error
+            .as_ref()
+            .or(self.secret_error.as_ref())
+            .or(self.permission_warning.as_ref())
+    }
+
+    // only a corrupt file gets to be moved aside, see `reset`
+    pub fn is_corrupt(&self) -> bool {
+        self.corrupt
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    // moves the unreadable file aside and starts over with an empty database
+    pub fn reset(&mut self) -> Result<(), DatabaseError> {
+        if Path::new(&self.path).exists() {
+            fs::rename(&self.path, format!("{}.corrupt.bak", self.path))?;
         }
+        *self = Database::new(self.path.clone());
+        Ok(())
     }
 
-    pub fn save(&self) -> Result<(), DatabaseError> {
-        let content = toml::to_string(self)?;
-        fs::write(&self.path, content)?;
+    // Hands the new tokens to the secret backend first, the secret command can take its time
+    // and shouldn't keep other instances waiting for the lock. Then waits for the file lock
+    // before taking the database, so the ui isn't stuck behind another instance's save, and
+    // only holds the database while merging and taking a snapshot of it. A file on the disk
+    // that can't be read stops the save rather than getting written over, the error is kept
+    // for the status bar.
+    pub fn save(db: &Mutex<Database>) -> Result<(), DatabaseError> {
+        let path = {
+            let db = db.lock().unwrap();
+            if db.read_only {
+                return Ok(());
+            }
+            db.path.clone()
+        };
+        let result = Database::store_secrets(db)
+            .and_then(|_| FileLock::acquire(&path))
+            .and_then(|_lock| {
+                // another instance might have written to the file since we last touched it
+                let disk = Database::read(&path)?;
+                let snapshot = db.lock().unwrap().snapshot(disk)?;
+                write_atomic(&path, toml::to_string(&snapshot.content)?.as_bytes())?;
+                db.lock().unwrap().written(snapshot);
+                Ok(())
+            });
+        db.lock().unwrap().save_error = result.as_ref().err().map(|err| err.to_string());
+        result
+    }
+
+    // stores the tokens the secret backend doesn't have yet, without holding the database
+    fn store_secrets(db: &Mutex<Database>) -> Result<(), DatabaseError> {
+        let (backend, secrets) = {
+            let db = db.lock().unwrap();
+            if db.secret_backend.keeps_tokens_in_file() {
+                return Ok(());
+            }
+            let secrets: Vec<(UserId, String)> = db
+                .accounts
+                .values()
+                .filter(|account| !account.token.is_empty())
+                .filter(|account| db.stored_secrets.get(&account.id) != Some(&account.token))
+                .map(|account| (account.id.clone(), account.token.clone()))
+                .collect();
+            (db.secret_backend.clone(), secrets)
+        };
+        for (id, token) in secrets {
+            backend.store().store(&id, &token)?;
+            db.lock().unwrap().stored_secrets.insert(id, token);
+        }
         Ok(())
     }
 
+    // what's on the disk right now, nothing if there's no file yet
+    fn read(path: &str) -> Result<Option<Database>, DatabaseError> {
+        match fs::read_to_string(path) {
+            Ok(raw) => Ok(Some(Database::parse(&raw)?.0)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn snapshot(&mut self, disk: Option<Database>) -> Result<Snapshot, DatabaseError> {
+        if let Some(disk) = disk {
+            self.merge(disk);
+        }
+        let mut content = toml::Value::try_from(&*self)?;
+        // only the tokens the secret backend has are left out, a token it didn't get yet
+        // stays in the file until it does
+        if !self.secret_backend.keeps_tokens_in_file() {
+            if let Some(accounts) = content.get_mut("accounts").and_then(|a| a.as_table_mut()) {
+                for account in self
+                    .accounts
+                    .values()
+                    .filter(|account| self.stored_secrets.get(&account.id) == Some(&account.token))
+                {
+                    if let Some(table) = accounts
+                        .get_mut(account.id.as_str())
+                        .and_then(|a| a.as_table_mut())
+                    {
+                        table.remove("token");
+                    }
+                }
+            }
+        }
+        Ok(Snapshot {
+            content,
+            synced: Synced {
+                active_account: self.active_account.clone(),
+                accounts: self.accounts.clone(),
+            },
+            changed_at: self.changed_at,
+        })
+    }
+
+    // the changes made while the snapshot was being written keep the database dirty
+    fn written(&mut self, snapshot: Snapshot) {
+        self.synced = snapshot.synced;
+        if self.changed_at == snapshot.changed_at {
+            self.changed_at = None;
+        }
+    }
+
+    fn mark_dirty(&mut self) {
+        self.changed_at = Some(Instant::now());
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.changed_at.is_some()
+    }
+
+    // dirty and left alone for long enough that more changes aren't likely to follow right away
+    fn should_flush(&self) -> bool {
+        self.changed_at
+            .is_some_and(|changed_at| changed_at.elapsed() >= FLUSH_DEBOUNCE)
+    }
+
+    pub fn set_first_load(&mut self, value: bool) {
+        self.first_load = value;
+        self.mark_dirty();
+    }
+
+    // the accounts left without a token end up on the token expired page once they're used
+    fn load_secrets(&mut self) {
+        let store = self.secret_backend.store();
+        let mut errors = vec![];
+        for account in self
+            .accounts
+            .values_mut()
+            .filter(|account| account.kind == AccountKind::Trello && account.token.is_empty())
+        {
+            match store.load(&account.id) {
+                Ok(Some(token)) => {
+                    self.stored_secrets
+                        .insert(account.id.clone(), token.clone());
+                    account.token = token;
+                }
+                Ok(None) => errors.push(format!("no token found for \"{}\"", account.username)),
+                Err(err) => errors.push(format!(
+                    "failed to load the token for \"{}\": {}",
+                    account.username, err
+                )),
+            }
+        }
+        self.secret_error = (!errors.is_empty()).then(|| errors.join(", "));
+    }
+
     pub fn add_user_account(&mut self, user: User, token: String) -> Result<(), DatabaseError> {
         let user_id = user.id.clone();
         let account = Account {
             id: user.id,
             username: user.username,
+            kind: AccountKind::Trello,
             token,
         };
         self.accounts.insert(user_id, account);
+        self.mark_dirty();
         Ok(())
     }
 
+    // the pseudo-account for boards that never touch Trello, there's only ever one
+    pub fn add_local_account(&mut self) -> UserId {
+        let account = Account {
+            id: UserId::unchecked(LOCAL_ACCOUNT),
+            username: LOCAL_USERNAME.to_string(),
+            kind: AccountKind::Local,
+            token: String::new(),
+        };
+        self.accounts.insert(account.id.clone(), account);
+        self.mark_dirty();
+        UserId::unchecked(LOCAL_ACCOUNT)
+    }
+
     pub fn active_account(&self) -> Option<&Account> {
         match &self.active_account {
             Some(active_account) => self.find_account(active_account),
@@ -86,6 +442,7 @@ impl Database {
     pub fn set_active_account(&mut self, id: UserId) -> Result<(), DatabaseError> {
         if self.accounts.contains_key(&id) {
             self.active_account = Some(id);
+            self.mark_dirty();
             Ok(())
         } else {
             Err(DatabaseError::KeyNotFound)
@@ -101,6 +458,91 @@ impl Database {
             Some(_id) if _id == id => self.active_account = None,
             _ => {}
         }
+        self.secret_backend.store().erase(id).ignore();
+        self.stored_secrets.remove(id);
+        self.mark_dirty();
         self.accounts.remove(id)
     }
 }
+
+// the database as it's about to be written, taken so the write doesn't need the database
+struct Snapshot {
+    content: toml::Value,
+    synced: Synced,
+    changed_at: Option<Instant>,
+}
+
+// keeps flushing the pending changes in the background so a crash doesn't lose them
+pub fn spawn_flusher(db: Arc<Mutex<Database>>) {
+    thread::spawn(move || loop {
+        thread::sleep(FLUSH_POLL_RATE);
+        let should_flush = match db.lock() {
+            Ok(db) => db.should_flush(),
+            Err(_) => break,
+        };
+        if should_flush {
+            // on failure the database stays dirty and gets retried on the next poll
+            Database::save(&db).ignore();
+        }
+    });
+}
+
+// writes into a temporary file next to the target and renames it over, so a crash mid-write
+// can't leave a truncated database behind. The name of the temporary file is unique to the
+// write, two of them can't end up in the same file.
+pub fn write_atomic(path: &str, content: &[u8]) -> Result<(), IoError> {
+    static WRITES: AtomicU32 = AtomicU32::new(0);
+    let tmp_path = format!(
+        "{}.{}.{}.tmp",
+        path,
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    );
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(FILE_MODE);
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    drop(file);
+    #[cfg(unix)]
+    fs::set_permissions(&tmp_path, fs::Permissions::from_mode(FILE_MODE))?;
+    if let Err(err) = fs::rename(&tmp_path, path) {
+        fs::remove_file(&tmp_path).ignore();
+        return Err(err);
+    }
+    sync_parent(path)
+}
+
+// the rename only survives a crash once the directory holding the file is on the disk too
+#[cfg(unix)]
+fn sync_parent(path: &str) -> Result<(), IoError> {
+    match Path::new(path).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => fs::File::open(dir)?.sync_all(),
+        _ => fs::File::open(".")?.sync_all(),
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent(_: &str) -> Result<(), IoError> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn loose_permissions(path: &Path) -> Option<String> {
+    let mode = fs::metadata(path).ok()?.permissions().mode() & 0o777;
+    (mode & !FILE_MODE != 0).then(|| {
+        format!(
+            "\"{}\" is readable by other users (mode {:o}), it should be {:o}",
+            path.display(),
+            mode,
+            FILE_MODE
+        )
+    })
+}
+
+#[cfg(not(unix))]
+fn loose_permissions(_: &Path) -> Option<String> {
+    None
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_CONNECT_TIMEOUT: u64 = 10;
+// generous, a big board on a slow link is still downloading when it runs out
+const DEFAULT_TIMEOUT: u64 = 120;
+
+fn default_connect_timeout() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT
+}
+
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT
+}
+
+// How the API client reaches Trello, the timeouts are in seconds.
+// Without `proxy` the usual HTTPS_PROXY/HTTP_PROXY/NO_PROXY variables are honoured.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NetworkConfig {
+    // talk to something else than https://api.trello.com/1, a mock server for example
+    pub endpoint: Option<String>,
+    pub proxy: Option<String>,
+    // PEM file with extra certificates to trust, for proxies that intercept TLS
+    pub ca_bundle: Option<String>,
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout: u64,
+    // for the whole request, from connecting to the last byte of the body, not between reads
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            proxy: None,
+            ca_bundle: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
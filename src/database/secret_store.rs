@@ -0,0 +1,121 @@
+use super::DatabaseError;
+use crate::models::UserId;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+const ID_PLACEHOLDER: &str = "{id}";
+
+pub trait SecretStore {
+    fn load(&self, id: &UserId) -> Result<Option<String>, DatabaseError>;
+    fn store(&self, id: &UserId, secret: &str) -> Result<(), DatabaseError>;
+    fn erase(&self, id: &UserId) -> Result<(), DatabaseError>;
+}
+
+// Keeps the tokens inside of the database file itself.
+pub struct FileSecretStore;
+
+impl SecretStore for FileSecretStore {
+    fn load(&self, _: &UserId) -> Result<Option<String>, DatabaseError> {
+        Ok(None)
+    }
+
+    fn store(&self, _: &UserId, _: &str) -> Result<(), DatabaseError> {
+        Ok(())
+    }
+
+    fn erase(&self, _: &UserId) -> Result<(), DatabaseError> {
+        Ok(())
+    }
+}
+
+// Delegates the tokens to an external helper such as `pass`, every `{id}` in the commands is
+// replaced with the account id and the secret itself is piped through stdin/stdout.
+// example:
+// get = "pass show trellminal/{id}"
+// store = "pass insert -m -f trellminal/{id}"
+// erase = "pass rm -f trellminal/{id}"
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CommandSecretStore {
+    pub get: String,
+    pub store: String,
+    pub erase: String,
+}
+
+impl CommandSecretStore {
+    fn command(template: &str, id: &UserId) -> Command {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(template.replace(ID_PLACEHOLDER, id.as_str()))
+            .stderr(Stdio::null());
+        command
+    }
+
+    fn check(status: std::process::ExitStatus, template: &str) -> Result<(), DatabaseError> {
+        if status.success() {
+            Ok(())
+        } else {
+            Err(DatabaseError::SecretStoreError(format!(
+                "\"{}\" exited with {}",
+                template, status
+            )))
+        }
+    }
+}
+
+impl SecretStore for CommandSecretStore {
+    fn load(&self, id: &UserId) -> Result<Option<String>, DatabaseError> {
+        let output = Self::command(&self.get, id).output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if secret.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(secret))
+        }
+    }
+
+    fn store(&self, id: &UserId, secret: &str) -> Result<(), DatabaseError> {
+        let mut child = Self::command(&self.store, id)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(secret.as_bytes())?;
+        } // close stdin
+        Self::check(child.wait()?, &self.store)
+    }
+
+    fn erase(&self, id: &UserId) -> Result<(), DatabaseError> {
+        let status = Self::command(&self.erase, id)
+            .stdout(Stdio::null())
+            .status()?;
+        Self::check(status, &self.erase)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SecretBackend {
+    #[default]
+    File,
+    Command(CommandSecretStore),
+}
+
+impl SecretBackend {
+    pub fn store(&self) -> &dyn SecretStore {
+        match self {
+            Self::File => &FileSecretStore,
+            Self::Command(store) => store,
+        }
+    }
+
+    pub fn keeps_tokens_in_file(&self) -> bool {
+        matches!(self, Self::File)
+    }
+}
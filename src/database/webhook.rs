@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const DEFAULT_PORT: u16 = 9998;
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+// Opt-in live updates, Trello has to reach `callback_url` (a tunnel or a public address)
+// which should forward to `http://localhost:{port}/webhook`.
+// `secret` is the application secret from https://trello.com/app-key used to sign the payloads.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct WebhookConfig {
+    pub callback_url: String,
+    pub secret: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+impl fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookConfig")
+            .field("callback_url", &self.callback_url)
+            .field("secret", &"[redacted]")
+            .field("port", &self.port)
+            .finish()
+    }
+}
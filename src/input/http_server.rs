@@ -1,5 +1,6 @@
 use super::{Event, EventSender};
 use std::{
+    collections::HashMap,
     fs,
     io::{prelude::*, BufReader, Error as IoError, ErrorKind},
     net::{TcpListener, TcpStream},
@@ -9,25 +10,57 @@ use std::{
     time::Duration,
 };
 
-pub type Port = &'static str;
+pub type Port = u16;
 pub type Validator = fn(&Request) -> bool;
 
+pub const STATUS_OK: &str = "HTTP/1.1 200 OK";
+pub const STATUS_UNAUTHORIZED: &str = "HTTP/1.1 401 Unauthorized";
+pub const STATUS_PAYLOAD_TOO_LARGE: &str = "HTTP/1.1 413 Payload Too Large";
+
+// webhook payloads are a few KiB, anything past this isn't from Trello
+const MAX_BODY_LEN: usize = 1024 * 1024;
+// a client that stops sending mid request would hold up every connection after it
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct Request {
+    method: String,
     url: String,
+    headers: HashMap<String, String>,
+    body: String,
     stream: TcpStream,
 }
 
 impl Request {
+    pub fn method(&self) -> &String {
+        &self.method
+    }
+
     pub fn url(&self) -> &String {
-        return &self.url;
+        &self.url
+    }
+
+    // header names are case insensitive so they are looked up in lowercase
+    pub fn header(&self, name: &str) -> Option<&String> {
+        self.headers.get(&name.to_lowercase())
     }
 
-    pub fn respond(mut self, content: String) -> Result<(), IoError> {
-        let status_line = "HTTP/1.1 200 OK";
+    pub fn body(&self) -> &String {
+        &self.body
+    }
+
+    pub fn respond(self, content: String) -> Result<(), IoError> {
+        self.respond_with_status(STATUS_OK, content)
+    }
+
+    pub fn respond_with_status(
+        mut self,
+        status_line: &str,
+        content: String,
+    ) -> Result<(), IoError> {
         let length = content.len();
         let response = format!("{status_line}\r\nContent-Length: {length}\r\n\r\n{content}");
 
-        return self.stream.write_all(response.as_bytes());
+        self.stream.write_all(response.as_bytes())
     }
 }
 
@@ -51,31 +84,62 @@ pub struct HttpServer {
     thread_message_broker: Sender<ThreadMessage>,
 }
 
-fn handle_connection(mut stream: TcpStream) -> Option<Request> {
-    let buf_reader = BufReader::new(&mut stream);
-    let http_request: Vec<_> = buf_reader
-        .lines()
-        .map(|result| result.unwrap())
-        .take_while(|line| !line.is_empty())
-        .collect();
+fn handle_connection(stream: TcpStream) -> Option<Request> {
+    // accepted streams can inherit non-blocking mode, where the timeout does nothing
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(READ_TIMEOUT)).ok()?;
+    let mut buf_reader = BufReader::new(stream);
+    let mut http_request = Vec::new();
+    loop {
+        let mut line = String::new();
+        match buf_reader.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if line.trim_end().is_empty() => break,
+            Ok(_) => http_request.push(line.trim_end().to_string()),
+        }
+    }
 
     let first_line = match http_request.first() {
-        Some(params) => params,
+        Some(params) => params.as_str(),
         None => "BAD REQUEST",
     };
 
     let mut params = first_line.split(' ');
 
-    // check if first token is GET as we only process get requests
-    if params.next().unwrap_or("") != "GET" {
+    // we only process get requests, plus the head and post requests webhooks are made of
+    let method = params.next().unwrap_or("");
+    if !matches!(method, "GET" | "HEAD" | "POST") {
         return None;
     }
 
     let url = params.next().unwrap_or("");
 
+    let headers: HashMap<String, String> = http_request
+        .iter()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_LEN {
+        let mut stream = buf_reader.into_inner();
+        let response = format!("{STATUS_PAYLOAD_TOO_LARGE}\r\nContent-Length: 0\r\n\r\n");
+        stream.write_all(response.as_bytes()).unwrap_or(());
+        return None;
+    }
+    let mut body = vec![0; length];
+    buf_reader.read_exact(&mut body).ok()?;
+
     Some(Request {
+        method: method.to_string(),
         url: url.to_string(),
-        stream,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+        stream: buf_reader.into_inner(),
     })
 }
 
@@ -89,6 +153,10 @@ fn background_worker(
     for stream in socket.incoming() {
         match stream {
             Ok(s) => match handle_connection(s) {
+                // webhook endpoints get checked with a HEAD request, answer it right away
+                Some(req) if req.method() == "HEAD" && validator(&req) => {
+                    req.respond(String::new()).unwrap_or(());
+                }
                 Some(req) => {
                     if validator(&req) {
                         event_sender.send(Event::Request(req)).unwrap();
@@ -115,13 +183,22 @@ fn background_worker(
 
 impl HttpServer {
     pub fn new(event_sender: EventSender, port: Port, validator: Validator) -> Self {
+        Self::bind(event_sender, port, validator).unwrap()
+    }
+
+    // same as `new` but lets the caller deal with the port being taken
+    pub fn bind(
+        event_sender: EventSender,
+        port: Port,
+        validator: Validator,
+    ) -> Result<Self, IoError> {
         let (tx, rx) = mpsc::channel();
-        let socket = TcpListener::bind(format!("0.0.0.0:{port}")).unwrap();
-        socket.set_nonblocking(true).unwrap();
+        let socket = TcpListener::bind(format!("0.0.0.0:{port}"))?;
+        socket.set_nonblocking(true)?;
         thread::spawn(move || background_worker(socket, event_sender, rx, validator));
-        Self {
+        Ok(Self {
             thread_message_broker: tx,
-        }
+        })
     }
 }
 
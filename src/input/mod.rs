@@ -1,6 +1,8 @@
 pub mod http_server;
+pub mod signals;
+pub mod webhook;
 
-use crossterm::event::{self, Event as CEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{self, Event as CEvent, KeyEventKind};
 use std::{
     convert::Into,
     default::Default,
@@ -10,12 +12,14 @@ use std::{
 };
 use tui_textarea::{Input, Key};
 
+use crate::api::SendRequestError;
 use crate::DETLA_TIME;
 
 pub use http_server::Request;
 
 pub use crossterm::event::KeyCode;
 pub use crossterm::event::KeyEvent;
+pub use crossterm::event::KeyModifiers;
 
 pub type EventSender = Sender<Event>;
 pub type EventReceiver = Receiver<Event>;
@@ -24,6 +28,12 @@ pub type EventReceiver = Receiver<Event>;
 pub enum Event {
     Input(KeyEvent),
     Request(Request),
+    CacheRefreshed(String),
+    // nothing cached and the backend didn't answer either
+    CacheFailed(String, SendRequestError),
+    // whether the details made it to the clipboard
+    Copied(bool),
+    Terminate,
     #[default]
     Tick,
 }
@@ -0,0 +1,32 @@
+use super::{Event, EventSender};
+
+// turns the termination signals into a regular `Event::Terminate` so the main loop gets to
+// restore the terminal and flush the database before exiting
+#[cfg(unix)]
+pub fn listen(event_sender: EventSender) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let (mut terminate, mut hangup) = match (
+            signal(SignalKind::terminate()),
+            signal(SignalKind::hangup()),
+        ) {
+            (Ok(terminate), Ok(hangup)) => (terminate, hangup),
+            _ => return,
+        };
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = hangup.recv() => {}
+        }
+        event_sender.send(Event::Terminate).unwrap_or(());
+    });
+}
+
+#[cfg(not(unix))]
+pub fn listen(event_sender: EventSender) {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            event_sender.send(Event::Terminate).unwrap_or(());
+        }
+    });
+}
@@ -0,0 +1,137 @@
+use super::Request;
+use crate::models::{CardId, ListId};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha1::Sha1;
+
+pub const WEBHOOK_ROUTE: &str = "/webhook";
+const SIGNATURE_HEADER: &str = "x-trello-webhook";
+
+#[derive(Debug, Deserialize)]
+pub struct Payload {
+    pub action: Action,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Action {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub data: ActionData,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ActionData {
+    pub card: Option<ActionCard>,
+}
+
+// only the fields that took part in the action are sent along
+#[derive(Debug, Deserialize)]
+pub struct ActionCard {
+    pub id: CardId,
+    pub name: Option<String>,
+    #[serde(rename = "idList")]
+    pub id_list: Option<ListId>,
+    pub closed: Option<bool>,
+}
+
+pub fn is_webhook(req: &Request) -> bool {
+    req.url().starts_with(WEBHOOK_ROUTE)
+}
+
+// Trello signs every payload with base64(HMAC-SHA1(secret, body + callback url)),
+// see https://developer.atlassian.com/cloud/trello/guides/rest-api/webhooks/
+fn mac(secret: &str, body: &str, callback_url: &str) -> Hmac<Sha1> {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret.as_bytes()).expect("any key length works");
+    mac.update(body.as_bytes());
+    mac.update(callback_url.as_bytes());
+    mac
+}
+
+pub fn verify(req: &Request, secret: &str, callback_url: &str) -> bool {
+    let signature = req.header(SIGNATURE_HEADER).map(String::as_str);
+    is_signed(signature, req.body(), secret, callback_url)
+}
+
+fn is_signed(signature: Option<&str>, body: &str, secret: &str, callback_url: &str) -> bool {
+    let signature = match signature.map(|s| BASE64.decode(s)) {
+        Some(Ok(signature)) => signature,
+        _ => return false,
+    };
+    mac(secret, body, callback_url)
+        .verify_slice(&signature)
+        .is_ok()
+}
+
+pub fn parse(req: &Request) -> Option<Payload> {
+    serde_json::from_str(req.body()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPDATE_CARD: &str = include_str!("../../res/webhooks/update_card.json");
+    const DELETE_CARD: &str = include_str!("../../res/webhooks/delete_card.json");
+    const SECRET: &str = "s3cret";
+    const CALLBACK_URL: &str = "https://example.com/webhook";
+    // what `res/webhooks/replay.sh` signs `update_card.json` with for the above
+    const SIGNATURE: &str = "xwG5uQbnzfZwu3t/nrS0B9Oy1Js=";
+
+    #[test]
+    fn accepts_trellos_signature() {
+        assert!(is_signed(
+            Some(SIGNATURE),
+            UPDATE_CARD,
+            SECRET,
+            CALLBACK_URL
+        ));
+    }
+
+    #[test]
+    fn rejects_a_tampered_body() {
+        let tampered = UPDATE_CARD.replace("Live board updates", "Dead board updates");
+        assert!(!is_signed(Some(SIGNATURE), &tampered, SECRET, CALLBACK_URL));
+    }
+
+    #[test]
+    fn rejects_another_callback_url() {
+        let callback_url = "https://example.com/other";
+        assert!(!is_signed(
+            Some(SIGNATURE),
+            UPDATE_CARD,
+            SECRET,
+            callback_url
+        ));
+    }
+
+    #[test]
+    fn rejects_a_missing_or_garbled_signature() {
+        assert!(!is_signed(None, UPDATE_CARD, SECRET, CALLBACK_URL));
+        assert!(!is_signed(
+            Some("not base64!"),
+            UPDATE_CARD,
+            SECRET,
+            CALLBACK_URL
+        ));
+    }
+
+    // the payloads are captured against the mock's Roadmap board
+    #[test]
+    fn parses_the_recorded_payloads() {
+        let update: Payload = serde_json::from_str(UPDATE_CARD).unwrap();
+        assert_eq!(update.action.kind, "updateCard");
+        let card = update.action.data.card.unwrap();
+        assert_eq!(card.id.as_str(), "5f1a0c3e8d2b4a0017d20004");
+        assert_eq!(card.name.as_deref(), Some("Live board updates"));
+        assert_eq!(card.id_list.unwrap().as_str(), "5f1a0c3e8d2b4a0017c10003");
+        assert_eq!(card.closed, None);
+
+        let delete: Payload = serde_json::from_str(DELETE_CARD).unwrap();
+        assert_eq!(delete.action.kind, "deleteCard");
+        let card = delete.action.data.card.unwrap();
+        assert_eq!(card.id.as_str(), "5f1a0c3e8d2b4a0017d20005");
+        assert_eq!(card.name, None);
+    }
+}
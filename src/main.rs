@@ -1,10 +1,15 @@
 mod api;
+mod backend;
+mod cache;
 mod database;
 mod input;
+mod mock;
 mod models;
+mod outbox;
 mod router;
 mod ui;
 
+use std::sync::Arc;
 use home::home_dir as _home_dir;
 use std::error::Error;
 
@@ -15,6 +20,10 @@ const DARK_MODE: bool = true;
 const FRAME_RATE: u8 = 10;
 const DETLA_TIME: u64 = 1000 / (FRAME_RATE as u64);
 const DETLA_TIME_F64: f64 = (DETLA_TIME as f64) / 1000f64;
+// set to run against the built-in mock server with a demo account,
+// `memory` skips the server and serves the same data straight from memory
+const MOCK_VAR: &str = "TRELLMINAL_MOCK";
+const MOCK_MEMORY: &str = "memory";
 
 pub trait Ignore: Sized {
     fn ignore(self) -> () {}
@@ -39,29 +48,100 @@ fn db_path() -> String {
     }
 }
 
+fn data_dir() -> String {
+    match home_dir() {
+        Some(home) => format!("{}/.trellminal", home),
+        None => format!("{}/.trellminal", "~"),
+    }
+}
+
+// the demo keeps its cache and outbox away from the real ones
+fn data_dir_for(demo: bool) -> String {
+    if demo {
+        format!("{}/trellminal-mock", std::env::temp_dir().display())
+    } else {
+        data_dir()
+    }
+}
+
+fn cache_dir(demo: bool) -> String {
+    format!("{}/cache", data_dir_for(demo))
+}
+
+fn outbox_dir(demo: bool) -> String {
+    format!("{}/outbox", data_dir_for(demo))
+}
+
+fn local_dir(demo: bool) -> String {
+    format!("{}/local", data_dir_for(demo))
+}
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let mut api = api::Api::new(API_KEY.to_string());
-    let db = database::Database::load(db_path().as_str());
-    let initial_route = if db.accounts.is_empty() {
-        "/first_load"
-    } else if let Some(active_account) = &db.active_account {
-        api.auth(db.accounts.get(active_account).unwrap().token.clone());
-        "/"
-    } else {
-        "/switch_account"
+    let demo = std::env::var(MOCK_VAR).ok();
+    let mock = match demo.as_deref() {
+        Some(MOCK_MEMORY) | None => None,
+        Some(_) => Some(mock::MockServer::start().await?),
+    };
+    let mut db = match demo {
+        Some(_) => database::Database::demo(),
+        None => database::Database::load(db_path().as_str())
+            .unwrap_or_else(|err| database::Database::unreadable(db_path(), &err)),
+    };
+    let network = db.network.clone().unwrap_or_default();
+    let endpoint = match &mock {
+        Some(mock) => mock.endpoint().clone(),
+        None => network
+            .endpoint
+            .clone()
+            .unwrap_or(api::DEFAULT_ENDPOINT.to_string()),
+    };
+    let backend: Arc<dyn backend::Backend> = match demo.as_deref() {
+        Some(MOCK_MEMORY) => Arc::new(backend::Memory::new()),
+        _ => Arc::new(api::Trello::new(
+            API_KEY.to_string(),
+            endpoint,
+            api::client::build(&network)?,
+        )),
+    };
+    let mut api = api::Api::new(
+        backend,
+        cache::Cache::new(cache_dir(demo.is_some())),
+        outbox::Outbox::new(outbox_dir(demo.is_some())),
+        local_dir(demo.is_some()),
+    );
+    if demo.is_some() {
+        api.auth(mock::TOKEN.to_string());
+        let user = api.backend().members_me().await?;
+        let user_id = user.id.clone();
+        db.add_user_account(user, mock::TOKEN.to_string())?;
+        db.set_active_account(user_id)?;
+    }
+    // where a missing account or an unreadable database leads is up to the route guards
+    if let Some(account) = db.active_account() {
+        match account.kind {
+            database::AccountKind::Trello => api.auth(account.token.clone()),
+            database::AccountKind::Local => api.use_local(account.id.clone()),
+        }
+        api.set_account(Some(account.id.clone()));
     }
-    .to_string();
+    let initial_route = "/".to_string();
 
     let (event_sender, event_receiver) = input::init();
+    input::signals::listen(event_sender.clone());
     let mut context = ui::init(db, api, event_sender, initial_route)
         .await
         .unwrap();
+    database::spawn_flusher(context.db.clone());
+    outbox::spawn_replayer(context.api.clone(), context.event_sender.clone());
 
     let mut error: Option<Box<dyn Error>> = None;
 
     loop {
         let event = event_receiver.recv().unwrap_or_default();
+        if let input::Event::Terminate = event {
+            break;
+        }
         if let input::Event::Input(i) = event {
             if i.code == input::KeyCode::Char('c')
                 && i.modifiers
@@ -89,12 +169,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
         println!("{:?}", err);
     }
 
-    context
-        .db
-        .lock()
-        .unwrap()
-        .save()
-        .expect("Failed to sync database");
+    let dirty = context.db.lock().unwrap().is_dirty();
+    if dirty {
+        if let Err(err) = database::Database::save(&context.db) {
+            println!("Failed to sync database: {}", err);
+        }
+    }
 
     Ok(())
 }
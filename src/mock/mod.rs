@@ -0,0 +1,228 @@
+pub mod state;
+
+use serde_json::{json, Value};
+use state::{Response, State};
+use std::{
+    collections::HashMap,
+    io::Error as IoError,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+pub const TOKEN: &str = "mock";
+
+// checks the `Authorization: OAuth oauth_consumer_key="..", oauth_token=".."` header
+fn authorize(headers: &HashMap<String, String>) -> Result<(), Response> {
+    let token = headers
+        .get("authorization")
+        .and_then(|value| value.split("oauth_token=\"").nth(1))
+        .and_then(|rest| rest.split('"').next())
+        .unwrap_or("");
+    state::check_token(token)
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<Mutex<State>>) -> Result<(), IoError> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    // the api sends everything in the query, the body is only drained
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+
+    let mut parts = request_line.split(' ');
+    let method = parts.next().unwrap_or("");
+    let url = reqwest::Url::parse(&format!("http://mock{}", parts.next().unwrap_or("/")));
+    let (status, body) = match (authorize(&headers), url) {
+        (Err(response), _) => response,
+        (Ok(_), Err(_)) => (400, json!("invalid url")),
+        (Ok(_), Ok(url)) => {
+            let path: Vec<&str> = url.path().split('/').filter(|s| !s.is_empty()).collect();
+            let query = url.query_pairs().into_owned().collect();
+            state::route(&mut state.lock().unwrap(), method, &path, &query)
+        }
+    };
+
+    // errors are plain text, just like Trello
+    let content = match body {
+        Value::String(message) if status != 200 => message,
+        body => body.to_string(),
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        if status == 200 { "OK" } else { "Error" },
+        content.len(),
+        content
+    );
+    reader.into_inner().write_all(response.as_bytes()).await
+}
+
+// An in-process stand-in for the Trello API serving the fixtures in `res/mock`.
+// Changes are kept in memory for as long as it runs. Stops when dropped.
+pub struct MockServer {
+    endpoint: String,
+    worker: JoinHandle<()>,
+}
+
+impl MockServer {
+    pub async fn start() -> Result<Self, IoError> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let endpoint = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(State::new()));
+        let worker = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle_connection(stream, state.clone()));
+            }
+        });
+        Ok(Self { endpoint, worker })
+    }
+
+    pub fn endpoint(&self) -> &String {
+        &self.endpoint
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.worker.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{client, SendRequestError, Trello, INVALID_TOKEN};
+    use crate::backend::{Backend, Memory};
+    use crate::database::NetworkConfig;
+    use crate::models::{BoardId, CardId, ListId, OrganizationId};
+
+    const ROADMAP: &str = "5f1a0c3e8d2b4a0017b0a001";
+    const BACKLOG: &str = "5f1a0c3e8d2b4a0017c10001";
+    const DOING: &str = "5f1a0c3e8d2b4a0017c10002";
+
+    // the real client, pointed at a fresh mock
+    async fn trello(token: &str) -> (MockServer, Trello) {
+        let mock = MockServer::start().await.unwrap();
+        let client = client::build(&NetworkConfig::default()).unwrap();
+        let trello = Trello::new("key".to_string(), mock.endpoint().clone(), client);
+        trello.auth(token.to_string());
+        (mock, trello)
+    }
+
+    #[tokio::test]
+    async fn members_me() {
+        let (_mock, trello) = trello(TOKEN).await;
+        let user = Backend::members_me(&trello).await.unwrap();
+        assert_eq!(user.id.as_str(), "5f1a0c3e8d2b4a0017c0ffee");
+    }
+
+    #[tokio::test]
+    async fn boards_content() {
+        let (_mock, trello) = trello(TOKEN).await;
+        let id: BoardId = ROADMAP.parse().unwrap();
+        let content = Backend::boards_content(&trello, &id).await.unwrap();
+        assert_eq!(content.board.name, "Roadmap");
+        assert_eq!(content.lists.len(), 3);
+        assert_eq!(content.cards.len(), 6);
+        assert!(content.cards.iter().all(|card| card.id_board == id));
+    }
+
+    #[tokio::test]
+    async fn create_then_move_card() {
+        let (_mock, trello) = trello(TOKEN).await;
+        let backlog: ListId = BACKLOG.parse().unwrap();
+        let doing: ListId = DOING.parse().unwrap();
+        let card = Backend::cards_create(&trello, &backlog, "Write tests")
+            .await
+            .unwrap();
+        assert_eq!(card.name, "Write tests");
+        assert_eq!(card.id_list, backlog);
+
+        let name = "Write more tests".to_string();
+        let card = Backend::cards_update(&trello, &card.id, Some(&name), Some(&doing))
+            .await
+            .unwrap();
+        assert_eq!(card.name, name);
+        assert_eq!(card.id_list, doing);
+
+        let board: BoardId = ROADMAP.parse().unwrap();
+        let content = Backend::boards_content(&trello, &board).await.unwrap();
+        assert!(content.cards.iter().any(|known| known.id == card.id));
+    }
+
+    // the fields of every route in a batch are kept apart from the routes themselves
+    #[tokio::test]
+    async fn organizations_batch() {
+        let (_mock, trello) = trello(TOKEN).await;
+        let ids: Vec<OrganizationId> = ["5f1a0c3e8d2b4a0017a0e001", "5f1a0c3e8d2b4a0017a0e002"]
+            .iter()
+            .map(|id| id.parse().unwrap())
+            .collect();
+        let organizations = Backend::organizations_batch(&trello, &ids).await.unwrap();
+        let names: Vec<&str> = organizations
+            .iter()
+            .map(|organization| organization.display_name.as_str())
+            .collect();
+        assert_eq!(names, ["Acme Engineering", "Personal"]);
+    }
+
+    #[tokio::test]
+    async fn expired_token() {
+        let (_mock, trello) = trello(state::EXPIRED_TOKEN).await;
+        let err = Backend::members_me(&trello).await.unwrap_err();
+        assert!(matches!(err, SendRequestError::ExpiredToken));
+    }
+
+    // a token pasted with a line break in it can't go in a header
+    #[tokio::test]
+    async fn invalid_token() {
+        let (_mock, trello) = trello("mock\n").await;
+        let err = Backend::members_me(&trello).await.unwrap_err();
+        assert!(matches!(err, SendRequestError::Unauthorized(message) if message == INVALID_TOKEN));
+    }
+
+    #[tokio::test]
+    async fn unknown_card() {
+        let (_mock, trello) = trello(TOKEN).await;
+        let id: CardId = "5f1a0c3e8d2b4a0017d2ffff".parse().unwrap();
+        let err = Backend::cards_get(&trello, &id).await.unwrap_err();
+        assert!(matches!(err, SendRequestError::NotFound(_)));
+    }
+
+    // `TRELLMINAL_MOCK=memory` has to tell the same story without the http in between
+    #[tokio::test]
+    async fn memory_matches_http() {
+        let (_mock, trello) = trello(TOKEN).await;
+        let memory = Memory::new();
+        memory.auth(TOKEN.to_string());
+        let id: BoardId = ROADMAP.parse().unwrap();
+        let over_http = Backend::boards_content(&trello, &id).await.unwrap();
+        let in_memory = memory.boards_content(&id).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(over_http).unwrap(),
+            serde_json::to_value(in_memory).unwrap()
+        );
+
+        let missing: CardId = "5f1a0c3e8d2b4a0017d2ffff".parse().unwrap();
+        let err = memory.cards_get(&missing).await.unwrap_err();
+        assert!(matches!(err, SendRequestError::NotFound(_)));
+    }
+}
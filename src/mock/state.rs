@@ -0,0 +1,245 @@
+use crate::backend::timestamp;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// any token is accepted, except this one which always comes back expired
+pub const EXPIRED_TOKEN: &str = "expired";
+
+const NOT_FOUND: &str = "The requested resource was not found.";
+
+const MEMBER: &str = include_str!("../../res/mock/member.json");
+const ORGANIZATIONS: &str = include_str!("../../res/mock/organizations.json");
+const BOARDS: &str = include_str!("../../res/mock/boards.json");
+const LISTS: &str = include_str!("../../res/mock/lists.json");
+const CARDS: &str = include_str!("../../res/mock/cards.json");
+
+pub type Response = (u16, Value);
+
+// Everything the mock knows about, starting from the fixtures in `res/mock`.
+pub struct State {
+    member: Value,
+    organizations: Vec<Value>,
+    boards: Vec<Value>,
+    lists: Vec<Value>,
+    cards: Vec<Value>,
+    next_id: u64,
+}
+
+fn fixture<T: serde::de::DeserializeOwned>(raw: &str) -> T {
+    serde_json::from_str(raw).expect("fixtures are valid json")
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self {
+            member: fixture(MEMBER),
+            organizations: fixture(ORGANIZATIONS),
+            boards: fixture(BOARDS),
+            lists: fixture(LISTS),
+            cards: fixture(CARDS),
+            next_id: 0,
+        }
+    }
+
+    // looks like a Trello id, 24 hex digits starting with the second it was made in
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        format!("{:08x}{:016x}", secs, 0xfeed_0000_0000_0000 + self.next_id)
+    }
+
+    fn touch_board(&mut self, id_board: &Value, now: &str) {
+        if let Some(board) = find(&mut self.boards, id_board) {
+            board["dateLastActivity"] = json!(now);
+        }
+    }
+}
+
+fn find<'a>(models: &'a mut [Value], id: &Value) -> Option<&'a mut Value> {
+    models.iter_mut().find(|model| model["id"] == *id)
+}
+
+fn filter(models: &[Value], key: &str, id: &str) -> Value {
+    models
+        .iter()
+        .filter(|model| model[key] == id && model["closed"] != true)
+        .cloned()
+        .collect()
+}
+
+// the labels in use on the cards, each one once
+fn labels(cards: &Value) -> Value {
+    let mut labels: Vec<Value> = Vec::new();
+    cards
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|card| card["labels"].as_array())
+        .flatten()
+        .for_each(|label| {
+            if !labels.iter().any(|known| known["id"] == label["id"]) {
+                labels.push(label.clone());
+            }
+        });
+    json!(labels)
+}
+
+// `before` and `limit` the way Trello pages through cards, newest first
+fn page(models: Value, query: &HashMap<String, String>) -> Value {
+    let mut models: Vec<Value> = serde_json::from_value(models).unwrap_or_default();
+    if let Some(before) = query.get("before") {
+        models.retain(|model| model["id"].as_str().is_some_and(|id| id < before.as_str()));
+    }
+    models.sort_by(|lhs, rhs| rhs["id"].as_str().cmp(&lhs["id"].as_str()));
+    if let Some(limit) = query.get("limit").and_then(|limit| limit.parse().ok()) {
+        models.truncate(limit);
+    }
+    json!(models)
+}
+
+// a route of a `/batch`, answered under its status like Trello does
+fn batch_entry(state: &mut State, url: &str) -> Value {
+    let url = match reqwest::Url::parse(&format!("http://mock{}", url)) {
+        Ok(url) => url,
+        Err(_) => return json!({ "statusCode": 400, "message": "invalid url" }),
+    };
+    let path: Vec<&str> = url.path().split('/').filter(|s| !s.is_empty()).collect();
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    match route(state, "GET", &path, &query) {
+        (200, body) => json!({ "200": only(body, query.get("fields")) }),
+        (status, message) => json!({ "statusCode": status, "message": message }),
+    }
+}
+
+// what's left of a model after `fields`, Trello always sends the id along
+fn only(mut model: Value, fields: Option<&String>) -> Value {
+    if let (Some(model), Some(fields)) = (model.as_object_mut(), fields.filter(|f| *f != "all")) {
+        let fields: Vec<&str> = fields.split(',').collect();
+        model.retain(|key, _| key == "id" || fields.contains(&key.as_str()));
+    }
+    model
+}
+
+fn found(model: Option<&mut Value>) -> Response {
+    match model {
+        Some(model) => (200, model.clone()),
+        None => (404, json!(NOT_FOUND)),
+    }
+}
+
+// answers a request the way Trello would, `path` being the segments after the endpoint
+pub fn route(
+    state: &mut State,
+    method: &str,
+    path: &[&str],
+    query: &HashMap<String, String>,
+) -> Response {
+    let now = timestamp();
+    match (method, path) {
+        ("GET", ["members", "me"]) => (200, state.member.clone()),
+        ("GET", ["organizations", id]) => found(find(&mut state.organizations, &json!(id))),
+        ("GET", ["organizations", id, "boards"]) => {
+            (200, filter(&state.boards, "idOrganization", id))
+        }
+        ("GET", ["boards", id]) if query.contains_key("lists") => {
+            let mut board = match find(&mut state.boards, &json!(id)) {
+                Some(board) => board.clone(),
+                None => return (404, json!(NOT_FOUND)),
+            };
+            let cards = filter(&state.cards, "idBoard", id);
+            board["lists"] = filter(&state.lists, "idBoard", id);
+            board["labels"] = labels(&cards);
+            board["cards"] = cards;
+            (200, board)
+        }
+        ("GET", ["boards", id]) => found(find(&mut state.boards, &json!(id))),
+        ("GET", ["boards", id, "lists"]) => (200, filter(&state.lists, "idBoard", id)),
+        ("GET", ["boards", id, "cards"]) => (200, page(filter(&state.cards, "idBoard", id), query)),
+        ("GET", ["batch"]) => {
+            let urls = query.get("urls").cloned().unwrap_or_default();
+            let entries: Vec<Value> = urls.split(',').map(|url| batch_entry(state, url)).collect();
+            (200, json!(entries))
+        }
+        ("GET", ["cards", id]) => found(find(&mut state.cards, &json!(id))),
+        ("POST", ["cards"]) => {
+            let list = match query
+                .get("idList")
+                .and_then(|id| find(&mut state.lists, &json!(id)))
+            {
+                Some(list) => list.clone(),
+                None => return (400, json!("invalid value for idList")),
+            };
+            let id = state.new_id();
+            let card = json!({
+                "id": id,
+                "idBoard": list["idBoard"],
+                "idList": list["id"],
+                "idOrganization": list["idOrganization"],
+                "name": query.get("name").cloned().unwrap_or_default(),
+                "labels": [],
+                "url": format!("https://trello.com/c/{}", id),
+                "closed": false,
+                "dateLastActivity": now,
+            });
+            state.touch_board(&list["idBoard"], &now);
+            state.cards.push(card.clone());
+            (200, card)
+        }
+        ("PUT", ["cards", id]) => {
+            let card = match find(&mut state.cards, &json!(id)) {
+                Some(card) => card,
+                None => return (404, json!(NOT_FOUND)),
+            };
+            for (key, value) in query {
+                match key.as_str() {
+                    "name" | "idList" => card[key] = json!(value),
+                    "closed" => card[key] = json!(value == "true"),
+                    _ => {}
+                }
+            }
+            card["dateLastActivity"] = json!(now);
+            let card = card.clone();
+            state.touch_board(&card["idBoard"], &now);
+            (200, card)
+        }
+        ("POST", ["cards", id, "actions", "comments"]) => {
+            let card = match find(&mut state.cards, &json!(id)) {
+                Some(card) => card.clone(),
+                None => return (404, json!(NOT_FOUND)),
+            };
+            state.touch_board(&card["idBoard"], &now);
+            let action = json!({
+                "id": state.new_id(),
+                "type": "commentCard",
+                "date": now,
+                "data": { "text": query.get("text"), "card": { "id": card["id"], "name": card["name"] } },
+            });
+            (200, action)
+        }
+        ("POST", ["webhooks"]) => {
+            let webhook = json!({
+                "id": state.new_id(),
+                "callbackURL": query.get("callbackURL"),
+                "idModel": query.get("idModel"),
+                "active": true,
+            });
+            (200, webhook)
+        }
+        ("DELETE", ["webhooks", _]) => (200, json!({ "_value": null })),
+        _ => (404, json!(NOT_FOUND)),
+    }
+}
+
+pub fn check_token(token: &str) -> Result<(), Response> {
+    match token {
+        "" => Err((401, json!("invalid token"))),
+        EXPIRED_TOKEN => Err((401, json!("expired token"))),
+        _ => Ok(()),
+    }
+}
@@ -1,13 +1,31 @@
+use super::{nullable, BoardId, Card, Label, List};
 use serde::{Deserialize, Serialize};
 
-pub type BoardId = String;
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Board {
     pub id: BoardId,
     pub name: String,
+    #[serde(default, deserialize_with = "nullable")]
     pub desc: String,
+    #[serde(default, deserialize_with = "nullable")]
     pub url: String,
+    #[serde(default, deserialize_with = "nullable")]
     pub pinned: bool,
+    #[serde(default, deserialize_with = "nullable")]
     pub starred: bool,
+    #[serde(rename = "dateLastActivity", default)]
+    pub date_last_activity: Option<String>,
+}
+
+// a board along with everything on it, as Trello nests it when asked to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardContent {
+    #[serde(flatten)]
+    pub board: Board,
+    #[serde(default, deserialize_with = "nullable")]
+    pub lists: Vec<List>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub cards: Vec<Card>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub labels: Vec<Label>,
 }
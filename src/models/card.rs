@@ -1,18 +1,22 @@
-use super::Label;
+use super::{nullable, BoardId, CardId, Label, ListId, OrganizationId};
 use serde::{Deserialize, Serialize};
 
-pub type CardId = String;
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Card {
     pub id: CardId,
     #[serde(rename = "idBoard")]
-    pub id_board: String,
+    pub id_board: BoardId,
     #[serde(rename = "idList")]
-    pub id_list: String,
-    #[serde(rename = "idOrganization")]
-    pub id_organization: String,
+    pub id_list: ListId,
+    // not set for cards on personal boards
+    #[serde(rename = "idOrganization", default)]
+    pub id_organization: Option<OrganizationId>,
+    #[serde(default, deserialize_with = "nullable")]
     pub name: String,
+    #[serde(default, deserialize_with = "nullable")]
     pub labels: Vec<Label>,
+    #[serde(default, deserialize_with = "nullable")]
     pub url: String,
+    #[serde(rename = "dateLastActivity", default)]
+    pub date_last_activity: Option<String>,
 }
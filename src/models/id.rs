@@ -0,0 +1,114 @@
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("\"{0}\" is not a valid {1}")]
+pub struct IdError(String, &'static str);
+
+// what Trello's ids look like, a MongoDB ObjectId
+fn is_object_id(id: &str) -> bool {
+    id.len() == 24 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+// boards and cards can also be addressed by the short link in their url
+fn is_short_link(id: &str) -> bool {
+    id.len() == 8 && id.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+fn is_link_id(id: &str) -> bool {
+    is_object_id(id) || is_short_link(id)
+}
+
+// Declares an id type that can't be mixed up with any other. Anything coming from outside,
+// route params as much as what Trello or our own files hand back, goes through `parse` and
+// gets validated. Only ids minted in memory, like the ones of pending cards, use `unchecked`.
+// It and `as_str` are only there for the kinds that list them.
+macro_rules! id {
+    ($name:ident, $kind:literal, $valid:expr $(, $extra:ident)*) => {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(id: &str) -> Result<Self, IdError> {
+                if $valid(id) {
+                    Ok(Self(id.to_string()))
+                } else {
+                    Err(IdError(id.to_string(), $kind))
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(de::Error::custom)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        $(id!(@$extra $name);)*
+    };
+    (@unchecked $name:ident) => {
+        impl $name {
+            pub fn unchecked(id: impl Into<String>) -> Self {
+                Self(id.into())
+            }
+        }
+    };
+    (@created $name:ident) => {
+        impl $name {
+            // an ObjectId starts with the second it was made in, a short link doesn't tell
+            pub fn created(&self) -> Option<u64> {
+                is_object_id(&self.0)
+                    .then(|| u64::from_str_radix(&self.0[..8], 16).ok())
+                    .flatten()
+            }
+        }
+    };
+    (@as_str $name:ident) => {
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+    };
+}
+
+id!(BoardId, "board id", is_link_id, unchecked, as_str);
+id!(CardId, "card id", is_link_id, unchecked, as_str, created);
+id!(ListId, "list id", is_object_id, unchecked, as_str);
+id!(LabelId, "label id", is_object_id);
+id!(
+    OrganizationId,
+    "workspace id",
+    is_object_id,
+    unchecked,
+    as_str
+);
+id!(UserId, "user id", is_object_id, unchecked, as_str);
+id!(WebhookId, "webhook id", is_object_id, as_str);
@@ -1,13 +1,16 @@
+use super::{nullable, BoardId, LabelId};
 use serde::{Deserialize, Serialize};
 
-pub type LabelId = String;
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Label {
     pub id: LabelId,
     #[serde(rename = "idBoard")]
-    pub id_board: String,
+    pub id_board: BoardId,
+    #[serde(default, deserialize_with = "nullable")]
     pub name: String,
+    // `null` for labels without a color
+    #[serde(default, deserialize_with = "nullable")]
     pub color: String,
+    #[serde(default, deserialize_with = "nullable")]
     pub uses: u32,
 }
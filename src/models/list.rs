@@ -1,14 +1,15 @@
+use super::{nullable, BoardId, ListId, OrganizationId};
 use serde::{Deserialize, Serialize};
 
-pub type ListId = String;
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct List {
     pub id: ListId,
     #[serde(rename = "idBoard")]
-    pub id_board: String,
-    #[serde(rename = "idOrganization")]
-    pub id_organization: String,
+    pub id_board: BoardId,
+    // not set for lists on personal boards
+    #[serde(rename = "idOrganization", default)]
+    pub id_organization: Option<OrganizationId>,
+    #[serde(default, deserialize_with = "nullable")]
     pub name: String,
 }
 
@@ -1,13 +1,127 @@
+use serde::{Deserialize, Deserializer};
+
 mod board;
 mod card;
+mod id;
 mod label;
 mod list;
 mod organization;
 mod user;
+mod webhook;
+
+pub use board::{Board, BoardContent};
+pub use card::Card;
+pub use id::{BoardId, CardId, IdError, LabelId, ListId, OrganizationId, UserId, WebhookId};
+pub use label::Label;
+pub use list::List;
+pub use organization::Organization;
+pub use user::User;
+pub use webhook::Webhook;
+
+// Trello answers `null` for plenty of fields that are usually set, those read as the default.
+// Meant for `#[serde(default, deserialize_with = "nullable")]` so a missing field works too.
+fn nullable<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+// the mock serves `res/mock` as plain json, these keep it in line with the models
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::batch::BatchEntry;
+
+    fn fixture<T: serde::de::DeserializeOwned>(raw: &str) -> T {
+        serde_json::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn member() {
+        let user: User = fixture(include_str!("../../res/mock/member.json"));
+        assert!(!user.id_organizations.is_empty());
+    }
+
+    #[test]
+    fn organizations() {
+        let organizations: Vec<Organization> =
+            fixture(include_str!("../../res/mock/organizations.json"));
+        assert!(!organizations.is_empty());
+    }
+
+    #[test]
+    fn boards() {
+        let boards: Vec<Board> = fixture(include_str!("../../res/mock/boards.json"));
+        assert!(!boards.is_empty());
+    }
+
+    #[test]
+    fn lists() {
+        let boards: Vec<Board> = fixture(include_str!("../../res/mock/boards.json"));
+        let lists: Vec<List> = fixture(include_str!("../../res/mock/lists.json"));
+        assert!(lists
+            .iter()
+            .all(|list| boards.iter().any(|board| board.id == list.id_board)));
+    }
+
+    #[test]
+    fn cards() {
+        let cards: Vec<Card> = fixture(include_str!("../../res/mock/cards.json"));
+        assert!(!cards.is_empty());
+    }
+    // `res/recorded` is what Trello answers, with the ids swapped for the mock's
+
+    #[test]
+    fn boards_content() {
+        let content: BoardContent = fixture(include_str!("../../res/recorded/boards_content.json"));
+        assert_eq!(content.board.name, "Groceries");
+        assert_eq!(content.board.desc, "");
+        assert!(!content.board.pinned);
+        assert_eq!(content.lists.len(), 2);
+        assert!(content
+            .lists
+            .iter()
+            .all(|list| list.id_organization.is_none()));
+        assert_eq!(content.cards.len(), 2);
+        // a personal board's cards carry no workspace
+        assert!(content
+            .cards
+            .iter()
+            .all(|card| card.id_organization.is_none()));
+        assert_eq!(content.cards[0].labels[0].color, "");
+        assert_eq!(content.cards[1].date_last_activity, None);
+        assert_eq!(content.labels[1].name, "Weekly");
+    }
+
+    #[test]
+    fn batch() {
+        let entries: Vec<BatchEntry<Organization>> =
+            fixture(include_str!("../../res/recorded/batch_organizations.json"));
+        let found: Vec<&str> = entries
+            .iter()
+            .filter_map(|entry| entry.ok.as_ref())
+            .map(|organization| organization.display_name.as_str())
+            .collect();
+        assert_eq!(found, ["Acme Engineering", "Personal"]);
+        // the failed route in between comes without a "200"
+        assert!(entries[1].ok.is_none());
+        let personal = entries[2].ok.as_ref().unwrap();
+        assert_eq!(personal.desc, "");
+        assert_eq!(personal.team_type, "");
+    }
 
-pub use board::{Board, BoardId};
-pub use card::{Card, CardId};
-pub use label::{Label, LabelId};
-pub use list::{List, ListId};
-pub use organization::{Organization, OrganizationId};
-pub use user::{User, UserId};
+    #[test]
+    fn card() {
+        let card: Card = fixture(include_str!("../../res/recorded/card.json"));
+        assert_eq!(card.name, "Coffee");
+        assert_eq!(card.id_list.as_str(), "5f1a0c3e8d2b4a0017c10007");
+        assert_eq!(card.id_organization, None);
+        assert!(card.labels.is_empty());
+        assert_eq!(
+            card.date_last_activity.as_deref(),
+            Some("2024-03-02T09:00:00.000Z")
+        );
+    }
+}
@@ -1,15 +1,16 @@
+use super::{nullable, OrganizationId};
 use serde::{Deserialize, Serialize};
 
-pub type OrganizationId = String;
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Organization {
     pub id: OrganizationId,
     pub name: String,
     #[serde(rename = "displayName")]
     pub display_name: String,
+    #[serde(default, deserialize_with = "nullable")]
     pub desc: String,
+    #[serde(default, deserialize_with = "nullable")]
     pub url: String,
-    #[serde(rename = "teamType")]
+    #[serde(rename = "teamType", default, deserialize_with = "nullable")]
     pub team_type: String,
 }
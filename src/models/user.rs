@@ -1,13 +1,12 @@
+use super::{nullable, BoardId, OrganizationId, UserId};
 use serde::{Deserialize, Serialize};
 
-pub type UserId = String;
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct User {
     pub id: UserId,
     pub username: String,
-    #[serde(rename = "idBoards")]
-    pub id_boards: Vec<String>,
-    #[serde(rename = "idOrganizations")]
-    pub id_organizations: Vec<String>,
+    #[serde(rename = "idBoards", default, deserialize_with = "nullable")]
+    pub id_boards: Vec<BoardId>,
+    #[serde(rename = "idOrganizations", default, deserialize_with = "nullable")]
+    pub id_organizations: Vec<OrganizationId>,
 }
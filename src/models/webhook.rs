@@ -0,0 +1,12 @@
+use super::WebhookId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: WebhookId,
+    #[serde(rename = "callbackURL")]
+    pub callback_url: String,
+    #[serde(rename = "idModel")]
+    pub id_model: String,
+    pub active: bool,
+}
@@ -0,0 +1,610 @@
+use crate::api::{Api, SendRequestError};
+use crate::cache::keys;
+use crate::database;
+use crate::input::EventSender;
+use crate::models::{BoardId, Card, CardId, ListId, UserId};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const REPLAY_RATE: Duration = Duration::from_secs(5);
+// how far our clock and Trello's can be apart when telling whether a card is newer than a send
+const CLOCK_SKEW: u64 = 5 * 60;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    CreateCard {
+        id_board: BoardId,
+        id_list: ListId,
+        name: String,
+        // what the card goes by until Trello gives it an id, random so it can't collide
+        // with one left in the outbox by an earlier run
+        #[serde(default = "pending_id")]
+        pending_id: String,
+    },
+    UpdateCard {
+        id_board: BoardId,
+        id: CardId,
+        // `dateLastActivity` of the card as we saw it when the change was made
+        base: Option<String>,
+        name: Option<String>,
+        id_list: Option<ListId>,
+    },
+    Comment {
+        id_board: BoardId,
+        id: CardId,
+        text: String,
+    },
+}
+
+pub fn pending_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+impl Change {
+    pub fn id_board(&self) -> &BoardId {
+        match self {
+            Self::CreateCard { id_board, .. }
+            | Self::UpdateCard { id_board, .. }
+            | Self::Comment { id_board, .. } => id_board,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::CreateCard { name, .. } => format!("Create card \"{}\"", name),
+            Self::UpdateCard {
+                name: Some(name), ..
+            } => format!("Rename card to \"{}\"", name),
+            Self::UpdateCard {
+                id_list: Some(id_list),
+                ..
+            } => format!("Move card to list {}", id_list),
+            Self::UpdateCard { id, .. } => format!("Update card {}", id),
+            Self::Comment { text, .. } => format!("Comment \"{}\"", text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Conflict {
+    // the card got changed remotely after the change was queued
+    Changed { theirs: Card },
+    // the server refused to apply the change
+    Rejected { reason: String },
+    // Trello didn't answer when the change was sent, it might have it already
+    Unanswered,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Entry {
+    pub change: Change,
+    pub conflict: Option<Conflict>,
+    // when a send that got no answer went out, in seconds since the epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unanswered_since: Option<u64>,
+}
+
+pub enum Resolution {
+    KeepMine,
+    KeepTheirs,
+}
+
+#[derive(Default)]
+struct State {
+    account: Option<UserId>,
+    entries: VecDeque<Entry>,
+    // the file of the account couldn't be read, it's set aside or left alone
+    load_error: Option<String>,
+    // nothing gets written over a file that couldn't be set aside
+    writable: bool,
+    save_error: Option<String>,
+}
+
+// Persistent queue of the changes that are yet to reach Trello, replayed in order by
+// `spawn_replayer` and kept on the disk so they survive going offline or a restart.
+#[derive(Clone)]
+pub struct Outbox {
+    dir: PathBuf,
+    state: Arc<Mutex<State>>,
+}
+
+impl Outbox {
+    pub fn new(dir: String) -> Self {
+        Self {
+            dir: PathBuf::from(dir),
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    fn path(&self, account: &UserId) -> PathBuf {
+        self.dir.join(format!("{}.json", account))
+    }
+
+    // every account has an outbox of its own, switching loads the one left behind last time
+    pub fn scope(&self, account: Option<UserId>) {
+        let (entries, load_error, writable) = match &account {
+            Some(account) => self.load(account),
+            None => (VecDeque::new(), None, true),
+        };
+        let mut state = self.state.lock().unwrap();
+        state.account = account;
+        state.entries = entries;
+        state.load_error = load_error;
+        state.writable = writable;
+        state.save_error = None;
+    }
+
+    // Only a missing file is an empty outbox. One that can't be read is moved to `.bak` so
+    // the changes in it can still be recovered by hand, if even that fails it's left alone.
+    fn load(&self, account: &UserId) -> (VecDeque<Entry>, Option<String>, bool) {
+        let path = self.path(account);
+        let err = match fs::read_to_string(&path) {
+            Err(err) if err.kind() == ErrorKind::NotFound => return (VecDeque::new(), None, true),
+            Err(err) => err.to_string(),
+            Ok(raw) => match serde_json::from_str(&raw) {
+                Ok(entries) => return (entries, None, true),
+                Err(err) => err.to_string(),
+            },
+        };
+        let backup = path.with_extension("json.bak");
+        match fs::rename(&path, &backup) {
+            Ok(_) => (
+                VecDeque::new(),
+                Some(format!(
+                    "queued changes unreadable ({}), moved to {}",
+                    err,
+                    backup.display()
+                )),
+                true,
+            ),
+            Err(_) => (
+                VecDeque::new(),
+                Some(format!(
+                    "queued changes in {} unreadable ({}), not saving new ones",
+                    path.display(),
+                    err
+                )),
+                false,
+            ),
+        }
+    }
+
+    fn persist(&self, state: &mut State) {
+        let account = match &state.account {
+            Some(account) if state.writable => account,
+            _ => return,
+        };
+        let path = self.path(account);
+        let result = serde_json::to_string(&state.entries)
+            .map_err(|err| err.to_string())
+            .and_then(|content| {
+                fs::create_dir_all(&self.dir)
+                    .and_then(|_| {
+                        database::write_atomic(&path.to_string_lossy(), content.as_bytes())
+                    })
+                    .map_err(|err| err.to_string())
+            });
+        state.save_error = result
+            .err()
+            .map(|err| format!("can't save queued changes: {}", err));
+    }
+
+    fn modify<F>(&self, f: F)
+    where
+        F: FnOnce(&mut VecDeque<Entry>),
+    {
+        let mut state = self.state.lock().unwrap();
+        f(&mut state.entries);
+        self.persist(&mut state);
+    }
+
+    // what went wrong with the file of the account, for the status bar
+    pub fn warning(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.save_error.clone().or(state.load_error.clone())
+    }
+
+    pub fn push(&self, change: Change) {
+        self.modify(|entries| {
+            entries.push_back(Entry {
+                change,
+                conflict: None,
+                unanswered_since: None,
+            })
+        });
+    }
+
+    pub fn entries(&self) -> Vec<Entry> {
+        self.state.lock().unwrap().entries.iter().cloned().collect()
+    }
+
+    // the changes to the board that haven't reached Trello yet, oldest first
+    pub fn pending(&self, id_board: &BoardId) -> Vec<Change> {
+        self.state
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|entry| entry.change.id_board() == id_board)
+            .map(|entry| entry.change.clone())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    fn head(&self) -> Option<Entry> {
+        self.state.lock().unwrap().entries.front().cloned()
+    }
+
+    // the replay stops at the first conflict until the user resolves it
+    pub fn conflict(&self) -> Option<Entry> {
+        self.head().filter(|entry| entry.conflict.is_some())
+    }
+
+    fn pop(&self) {
+        self.modify(|entries| {
+            entries.pop_front();
+        });
+    }
+
+    // whether a change to the card is still waiting, after the one being sent
+    fn has_update(&self, id: &CardId) -> bool {
+        self.state.lock().unwrap().entries.iter().skip(1).any(
+            |entry| matches!(&entry.change, Change::UpdateCard { id: other, .. } if other == id),
+        )
+    }
+
+    // The changes queued after the one that just went through were made on top of it, not on
+    // top of what Trello had before. Their base moves on with the card or they'd conflict with it.
+    fn rebase(&self, id: &CardId, date_last_activity: Option<String>) {
+        self.modify(|entries| {
+            for entry in entries.iter_mut() {
+                if let Change::UpdateCard {
+                    id: other, base, ..
+                } = &mut entry.change
+                {
+                    if other == id && base.is_some() {
+                        base.clone_from(&date_last_activity);
+                    }
+                }
+            }
+        });
+    }
+
+    // the earliest send is kept, any of them could have been the one that went through
+    fn set_unanswered(&self, since: u64) {
+        self.modify(|entries| {
+            if let Some(entry) = entries.front_mut() {
+                entry.unanswered_since.get_or_insert(since);
+            }
+        });
+    }
+
+    fn set_conflict(&self, conflict: Conflict) {
+        self.modify(|entries| {
+            if let Some(entry) = entries.front_mut() {
+                entry.conflict = Some(conflict);
+            }
+        });
+    }
+
+    // a change Trello refused would only be refused again, it can't be kept. One it didn't
+    // answer gets sent again when it's kept.
+    pub fn resolve(&self, resolution: Resolution) {
+        self.modify(|entries| match resolution {
+            Resolution::KeepTheirs => {
+                entries.pop_front();
+            }
+            Resolution::KeepMine => {
+                if let Some(entry) = entries.front_mut() {
+                    if let Some(Conflict::Unanswered) = entry.conflict {
+                        entry.conflict = None;
+                        entry.unanswered_since = None;
+                    } else if let (
+                        Change::UpdateCard { base, .. },
+                        Some(Conflict::Changed { theirs }),
+                    ) = (&mut entry.change, &entry.conflict)
+                    {
+                        // overwrite whatever they did
+                        *base = theirs.date_last_activity.clone();
+                        entry.conflict = None;
+                    }
+                }
+            }
+        });
+    }
+}
+
+enum ReplayError {
+    // try again later, the network or the token isn't there
+    Offline,
+    // like offline, except the change might have gone through
+    Unanswered,
+    Conflict(Conflict),
+}
+
+impl ReplayError {
+    // sending a POST again after it got no answer could make a second card or comment
+    fn posted(err: SendRequestError) -> Self {
+        if err.is_ambiguous() {
+            Self::Unanswered
+        } else {
+            err.into()
+        }
+    }
+}
+
+impl From<SendRequestError> for ReplayError {
+    fn from(err: SendRequestError) -> Self {
+        match err {
+            SendRequestError::ExpiredToken | SendRequestError::Unauthorized(_) => Self::Offline,
+            err if err.is_transient() => Self::Offline,
+            err => Self::Conflict(Conflict::Rejected {
+                reason: err.to_string(),
+            }),
+        }
+    }
+}
+
+// sends the change, with the card as it ended up when the change bumped its activity
+async fn send(
+    entry: &Entry,
+    api: &Arc<Mutex<Api>>,
+    outbox: &Outbox,
+) -> Result<Option<Card>, ReplayError> {
+    let backend = api.lock().unwrap().backend();
+    match &entry.change {
+        Change::CreateCard {
+            id_board,
+            id_list,
+            name,
+            ..
+        } => {
+            // the card is there already if one like it showed up after the unanswered send
+            if let Some(since) = entry.unanswered_since {
+                let content = backend.boards_content(id_board).await?;
+                let landed = content.cards.iter().any(|card| {
+                    card.id_list == *id_list
+                        && card.name == *name
+                        && card
+                            .id
+                            .created()
+                            .is_some_and(|created| created + CLOCK_SKEW >= since)
+                });
+                if landed {
+                    return Ok(None);
+                }
+            }
+            backend
+                .cards_create(id_list, name)
+                .await
+                .map_err(ReplayError::posted)?;
+            Ok(None)
+        }
+        Change::UpdateCard {
+            id,
+            base,
+            name,
+            id_list,
+            ..
+        } => {
+            if base.is_some() {
+                let theirs = backend.cards_get(id).await?;
+                if theirs.date_last_activity != *base {
+                    return Err(ReplayError::Conflict(Conflict::Changed { theirs }));
+                }
+            }
+            let card = backend
+                .cards_update(id, name.as_ref(), id_list.as_ref())
+                .await?;
+            Ok(Some(card))
+        }
+        // there's no telling whether a comment went through, the user has to look
+        Change::Comment { .. } if entry.unanswered_since.is_some() => {
+            Err(ReplayError::Conflict(Conflict::Unanswered))
+        }
+        Change::Comment { id, text, .. } => {
+            backend
+                .cards_comment(id, text)
+                .await
+                .map_err(ReplayError::posted)?;
+            // a comment is activity too, only worth looking up when a change comes after it
+            if outbox.has_update(id) {
+                Ok(backend.cards_get(id).await.ok())
+            } else {
+                Ok(None)
+            }
+        }
+    }
+}
+
+async fn replay(api: &Arc<Mutex<Api>>, event_sender: &EventSender) {
+    let (outbox, cache) = {
+        // lock api
+        let api = api.lock().unwrap();
+        (api.outbox(), api.cache())
+    }; // release api
+    while let Some(entry) = outbox.head() {
+        if entry.conflict.is_some() {
+            return;
+        }
+        let sent_at = now();
+        match send(&entry, api, &outbox).await {
+            Ok(card) => {
+                outbox.pop();
+                if let Some(card) = card {
+                    outbox.rebase(&card.id, card.date_last_activity);
+                }
+                let id_board = entry.change.id_board();
+                let req = api.lock().unwrap().backend().boards_content(id_board);
+                cache
+                    .refresh(keys::board(id_board), req, event_sender)
+                    .await;
+            }
+            Err(ReplayError::Offline) => return,
+            Err(ReplayError::Unanswered) => {
+                outbox.set_unanswered(sent_at);
+                return;
+            }
+            Err(ReplayError::Conflict(conflict)) => {
+                outbox.set_conflict(conflict);
+                return;
+            }
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub fn spawn_replayer(api: Arc<Mutex<Api>>, event_sender: EventSender) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REPLAY_RATE);
+        loop {
+            interval.tick().await;
+            replay(&api, &event_sender).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{client, Trello};
+    use crate::cache::Cache;
+    use crate::database::NetworkConfig;
+    use crate::mock::{MockServer, TOKEN};
+    use std::sync::mpsc;
+
+    const ROADMAP: &str = "5f1a0c3e8d2b4a0017b0a001";
+    const BACKLOG: &str = "5f1a0c3e8d2b4a0017c10001";
+    const DOING: &str = "5f1a0c3e8d2b4a0017c10002";
+    const DONE: &str = "5f1a0c3e8d2b4a0017c10003";
+    const CARD: &str = "5f1a0c3e8d2b4a0017d20001";
+
+    fn temp_dir() -> String {
+        format!(
+            "{}/trellminal-test-{}",
+            std::env::temp_dir().display(),
+            pending_id()
+        )
+    }
+
+    // an api signed in to the mock, the mock has to outlive it
+    fn signed_in(mock: &MockServer, dir: &str) -> (Arc<Mutex<Api>>, Outbox) {
+        let client = client::build(&NetworkConfig::default()).unwrap();
+        let trello = Trello::new("key".to_string(), mock.endpoint().clone(), client);
+        let outbox = Outbox::new(format!("{}/outbox", dir));
+        let mut api = Api::new(
+            Arc::new(trello),
+            Cache::new(format!("{}/cache", dir)),
+            outbox.clone(),
+            format!("{}/local", dir),
+        );
+        api.auth(TOKEN.to_string());
+        api.set_account(Some("5f1a0c3e8d2b4a0017c0ffee".parse().unwrap()));
+        (Arc::new(Mutex::new(api)), outbox)
+    }
+
+    // H then H on the same card, both queued before either reached Trello
+    #[tokio::test]
+    async fn replays_two_moves_of_one_card() {
+        let mock = MockServer::start().await.unwrap();
+        let dir = temp_dir();
+        let (api, outbox) = signed_in(&mock, &dir);
+        let backend = api.lock().unwrap().backend();
+
+        let id: CardId = CARD.parse().unwrap();
+        let base = backend.cards_get(&id).await.unwrap().date_last_activity;
+        assert!(base.is_some());
+        for to in [DOING, DONE] {
+            outbox.push(Change::UpdateCard {
+                id_board: ROADMAP.parse().unwrap(),
+                id: id.clone(),
+                base: base.clone(),
+                name: None,
+                id_list: Some(to.parse().unwrap()),
+            });
+        }
+
+        let (event_sender, _event_receiver) = mpsc::channel();
+        replay(&api, &event_sender).await;
+        assert!(outbox.conflict().is_none());
+        assert_eq!(outbox.len(), 0);
+        let card = backend.cards_get(&id).await.unwrap();
+        assert_eq!(card.id_list.as_str(), DONE);
+        fs::remove_dir_all(dir).unwrap_or(());
+    }
+
+    // the create went through but the answer got lost on the way back
+    #[tokio::test]
+    async fn creates_a_card_once_after_no_answer() {
+        let mock = MockServer::start().await.unwrap();
+        let dir = temp_dir();
+        let (api, outbox) = signed_in(&mock, &dir);
+        let backend = api.lock().unwrap().backend();
+
+        let id_board: BoardId = ROADMAP.parse().unwrap();
+        let id_list: ListId = BACKLOG.parse().unwrap();
+        outbox.push(Change::CreateCard {
+            id_board: id_board.clone(),
+            id_list: id_list.clone(),
+            name: "Fix: crash".to_string(),
+            pending_id: pending_id(),
+        });
+        outbox.set_unanswered(now());
+        backend.cards_create(&id_list, "Fix: crash").await.unwrap();
+
+        let (event_sender, _event_receiver) = mpsc::channel();
+        replay(&api, &event_sender).await;
+        assert!(outbox.conflict().is_none());
+        assert_eq!(outbox.len(), 0);
+        let content = backend.boards_content(&id_board).await.unwrap();
+        let created = content
+            .cards
+            .iter()
+            .filter(|card| card.name == "Fix: crash")
+            .count();
+        assert_eq!(created, 1);
+        fs::remove_dir_all(dir).unwrap_or(());
+    }
+
+    #[tokio::test]
+    async fn leaves_an_unanswered_comment_to_the_user() {
+        let mock = MockServer::start().await.unwrap();
+        let dir = temp_dir();
+        let (api, outbox) = signed_in(&mock, &dir);
+
+        outbox.push(Change::Comment {
+            id_board: ROADMAP.parse().unwrap(),
+            id: CARD.parse().unwrap(),
+            text: "on it".to_string(),
+        });
+        outbox.set_unanswered(now());
+
+        let (event_sender, _event_receiver) = mpsc::channel();
+        replay(&api, &event_sender).await;
+        let entry = outbox.conflict().unwrap();
+        assert!(matches!(entry.conflict, Some(Conflict::Unanswered)));
+
+        outbox.resolve(Resolution::KeepMine);
+        replay(&api, &event_sender).await;
+        assert_eq!(outbox.len(), 0);
+        fs::remove_dir_all(dir).unwrap_or(());
+    }
+}
@@ -0,0 +1,27 @@
+use super::{Api, Database};
+
+// what a guard makes of a navigation, before the page gets to mount
+pub enum Verdict {
+    Pass,
+    // somewhere else has to come first, like adding an account
+    Redirect(String),
+}
+
+// Guards run in the order the route was given them, the first one that doesn't pass has
+// the last word. They lock whatever they look at themselves.
+pub type Guard = fn(&Database, &Api) -> Verdict;
+
+// an account added after going on read-only is as good as any
+pub fn requires_account(db: &Database, _api: &Api) -> Verdict {
+    let db = db.lock().unwrap();
+    if db.active_account().is_some() {
+        Verdict::Pass
+    } else if db.load_error().is_some() {
+        Verdict::Redirect("/database_unreadable".to_string())
+    } else if db.accounts.is_empty() {
+        Verdict::Redirect("/first_load".to_string())
+    } else {
+        // none of the accounts is in use, signing in again picks one
+        Verdict::Redirect("/authenticate".to_string())
+    }
+}
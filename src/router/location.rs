@@ -0,0 +1,58 @@
+use super::Params;
+
+// what can go into a path segment or a query value as it is, everything else is escaped
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+// percent-encodes a value for a location, so a `/` or a `?` in it can't split it up
+pub fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            if is_unreserved(byte) {
+                (byte as char).to_string()
+            } else {
+                format!("%{:02X}", byte)
+            }
+        })
+        .collect()
+}
+
+// `None` for a broken escape or anything that doesn't decode to UTF-8
+pub fn decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+// the part the routes match on and the query string after the `?`, if any
+pub fn split(location: &str) -> (&str, Option<&str>) {
+    match location.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (location, None),
+    }
+}
+
+// `key=value` pairs, a key without a value gets an empty one and broken pairs are skipped
+pub fn parse_query(query: &str) -> Params {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let key = decode(&key.replace('+', " "))?;
+            let value = decode(&value.replace('+', " "))?;
+            Some((key, value))
+        })
+        .collect()
+}
@@ -1,21 +1,28 @@
+pub mod guard;
+mod location;
 pub mod page;
 mod routes;
 mod with_params;
 
-use crate::Ignore;
-use page::{MountOperation, MountResult, Page};
+pub use location::encode;
+
+use guard::{Guard, Verdict};
+use page::{MountOperation, Page, Snapshot};
 use routes::Routes;
 
 use crate::api::{Api as RawApi, SendRequestError};
 use crate::database::Database as RawDatabase;
 use crate::input::{Event, EventSender};
+use crate::models::IdError;
 use async_recursion::async_recursion;
+use itertools::Itertools;
 use once_cell::sync::Lazy;
 use std::{
     collections::HashMap,
     io::Stdout,
     sync::{Arc, Mutex},
 };
+use tokio::sync::{Mutex as TkMutex, Notify};
 use tui::{backend::CrosstermBackend, Frame as TFrame};
 
 pub type Params = HashMap<String, String>;
@@ -28,46 +35,155 @@ pub enum Operation {
     None,
     Navigate(String),
     NavigateBackward,
+    NavigateForward,
+    // the page can't go on, the router takes it from there like it does a failed mount
+    Fail(anyhow::Error),
     Consume,
     Exit,
 }
 
 pub struct Router {
-    history: Vec<String>,
+    history: Vec<Entry>,
+    // where going back came from, gone with the next new location
+    forward: Vec<Entry>,
     routes: Routes,
+    // checked in order before the page of the route mounts, by route
+    guards: HashMap<String, Vec<Guard>>,
+    navigation: Option<Navigation>,
+}
+
+// a location in the history, with how its page looked when it was left
+struct Entry {
+    location: String,
+    snapshot: Option<Snapshot>,
+}
+
+impl Entry {
+    fn new(location: String) -> Self {
+        Self {
+            location,
+            snapshot: None,
+        }
+    }
+}
+
+// how a navigation moves through the history once the page is mounted
+enum Step {
+    Push,
+    // in place of the current location, for pages standing in for another
+    Replace,
+    Back,
+    Forward,
+}
+
+// A page being mounted. Until it's done the current page stays on the screen and keeps
+// taking the input, unless it's the very page being mounted again.
+struct Navigation {
+    location: String,
+    cancel: Arc<Notify>,
+    keeps_current: bool,
 }
 
 static NOT_FOUND_ROUTE: Lazy<String> = Lazy::new(|| "/404".to_string());
 static TOKEN_EXPIRED_ROUTE: Lazy<String> = Lazy::new(|| "/token_expired".to_string());
 static ERROR_ROUTE: Lazy<String> = Lazy::new(|| "/error".to_string());
 
+// guards or pages sending the user back and forth give up after this many redirects
+const MAX_REDIRECTS: u8 = 8;
+// how many led to a navigation, it travels in the params but never reaches the page
+const REDIRECTS_PARAM: &str = "redirects";
+
+fn redirect_params(redirects: u8) -> Params {
+    let mut params = Params::new();
+    params.insert(REDIRECTS_PARAM.to_string(), redirects.to_string());
+    params
+}
+
+// where a failure takes the user, `Err` when it's for the error page to show
+fn recover(err: anyhow::Error) -> Result<String, anyhow::Error> {
+    if let Some(req_err) = err.downcast_ref::<SendRequestError>() {
+        match req_err {
+            SendRequestError::ExpiredToken | SendRequestError::Unauthorized(_) => {
+                return Ok(TOKEN_EXPIRED_ROUTE.to_owned());
+            }
+            SendRequestError::NotFound(_) => return Ok(NOT_FOUND_ROUTE.to_owned()),
+            _ => {}
+        }
+    } else if err.downcast_ref::<IdError>().is_some() {
+        // a malformed id in the location can't point at anything
+        return Ok(NOT_FOUND_ROUTE.to_owned());
+    }
+    Err(err)
+}
+
+// the error travels in the params, the location stays a plain route
+fn error_params(failed: String, err: anyhow::Error) -> Params {
+    let mut params = Params::new();
+    params.insert("failed".to_string(), failed);
+    params.insert(
+        "error".to_string(),
+        err.chain().map(|cause| cause.to_string()).join("\n"),
+    );
+    params
+}
+
 impl Router {
     pub fn new() -> Self {
         Self {
             history: vec![],
+            forward: vec![],
             routes: Routes::new(),
+            guards: HashMap::new(),
+            navigation: None,
         }
     }
 
     pub fn peek(&self) -> &String {
-        let len = self.history.len();
-        if len > 0 {
-            &self.history[len - 1]
-        } else {
-            &NOT_FOUND_ROUTE
+        match self.history.last() {
+            Some(entry) => &entry.location,
+            None => &NOT_FOUND_ROUTE,
         }
     }
 
-    fn pop(&mut self) -> Result<String, &String> {
-        if self.history.len() <= 1 {
-            Err(self.peek())
-        } else {
-            Ok(self.history.pop().unwrap())
+    fn push(&mut self, entry: Entry) {
+        // the error page only stands in for the location that failed, whatever comes next
+        // takes its place
+        if *self.peek() == *ERROR_ROUTE {
+            self.history.pop();
         }
+        self.history.push(entry);
     }
 
-    fn push(&mut self, location: String) {
-        self.history.push(location);
+    // remembers how the current page looks for when the history comes back to it
+    fn keep_snapshot(&mut self) {
+        let snapshot = self.current().and_then(|page| page.snapshot());
+        if let Some(entry) = self.history.last_mut() {
+            entry.snapshot = snapshot;
+        }
+    }
+
+    fn step(&mut self, step: Step, location: String) {
+        match step {
+            Step::Push => {
+                self.forward.clear();
+                self.push(Entry::new(location));
+            }
+            Step::Replace => {
+                self.history.pop();
+                self.push(Entry::new(location));
+            }
+            Step::Back => {
+                // the error page has nothing to come back to without the error
+                if let Some(entry) = self.history.pop().filter(|e| e.location != *ERROR_ROUTE) {
+                    self.forward.push(entry);
+                }
+            }
+            Step::Forward => {
+                if let Some(entry) = self.forward.pop() {
+                    self.push(entry);
+                }
+            }
+        }
     }
 
     pub fn route<P>(mut self, location: String, page: P) -> Self
@@ -78,6 +194,14 @@ impl Router {
         self
     }
 
+    pub fn guarded<P>(mut self, location: String, page: P, guards: &[Guard]) -> Self
+    where
+        P: Page + 'static,
+    {
+        self.guards.insert(location.clone(), guards.to_vec());
+        self.route(location, page)
+    }
+
     pub fn not_found<P>(self, page: P) -> Self
     where
         P: Page + 'static,
@@ -85,98 +209,287 @@ impl Router {
         self.route(NOT_FOUND_ROUTE.to_owned(), page)
     }
 
-    #[async_recursion]
+    pub fn error<P>(self, page: P) -> Self
+    where
+        P: Page + 'static,
+    {
+        self.route(ERROR_ROUTE.to_owned(), page)
+    }
+
+    // where the navigation still mounting is headed
+    pub fn navigating(&self) -> Option<&String> {
+        self.navigation
+            .as_ref()
+            .map(|navigation| &navigation.location)
+    }
+
+    // what the guards of the location's route make of it, the first one not passing wins
+    fn check(&self, location: &str, db: &Database, api: &Api) -> Verdict {
+        self.routes
+            .route_of(location)
+            .and_then(|route| self.guards.get(route))
+            .into_iter()
+            .flatten()
+            .map(|guard| guard(db, api))
+            .find(|verdict| !matches!(verdict, Verdict::Pass))
+            .unwrap_or(Verdict::Pass)
+    }
+
+    // gives up on the navigation still mounting, there has to be a page to stay on
+    pub fn cancel_navigation(&mut self) -> bool {
+        match self.navigation.take() {
+            Some(navigation) if navigation.keeps_current => {
+                navigation.cancel.notify_one();
+                true
+            }
+            navigation => {
+                self.navigation = navigation;
+                false
+            }
+        }
+    }
+
     pub async fn navigate(
-        &mut self,
+        router: &Arc<TkMutex<Router>>,
         location: String,
         db: &Database,
         api: &Api,
         event_sender: &EventSender,
     ) {
-        let mut params = Params::new();
-        params.insert("location".to_string(), location.clone());
-        params.insert("origin".to_string(), self.peek().clone());
-
-        let location = if self.routes.contains_location(&location) {
-            location
-        } else {
-            NOT_FOUND_ROUTE.clone()
-        };
-        self.unmount_current(db, api).await;
+        Self::navigate_with(
+            router,
+            location,
+            Params::new(),
+            Step::Push,
+            db,
+            api,
+            event_sender,
+        )
+        .await
+    }
 
-        match self
-            .mount_page(location.clone(), db, api, event_sender, params)
+    pub async fn navigate_backward(
+        router: &Arc<TkMutex<Router>>,
+        db: &Database,
+        api: &Api,
+        event_sender: &EventSender,
+    ) {
+        let previous = {
+            let router = router.lock().await;
+            let len = router.history.len();
+            (len > 1).then(|| router.history[len - 2].location.clone())
+        };
+        if let Some(location) = previous {
+            Self::navigate_with(
+                router,
+                location,
+                Params::new(),
+                Step::Back,
+                db,
+                api,
+                event_sender,
+            )
             .await
-        {
-            Ok(op) => {
-                self.push(location);
-                match op {
-                    MountOperation::Redirect(loc) => {
-                        self.navigate(loc, db, api, event_sender).await
-                    }
-                    MountOperation::None => {}
-                }
-            }
-            Err(err) => {
-                self.navigate(
-                    format!("{}/{}", ERROR_ROUTE.to_owned(), err.to_string()),
-                    db,
-                    api,
-                    event_sender,
-                )
-                .await
-            }
         }
     }
 
-    pub async fn navigate_backward(
-        &mut self,
+    pub async fn navigate_forward(
+        router: &Arc<TkMutex<Router>>,
         db: &Database,
         api: &Api,
         event_sender: &EventSender,
     ) {
-        self.pop().ignore();
-        if let Ok(loc) = self.pop() {
-            self.navigate(loc, db, api, event_sender).await;
+        let next = {
+            let router = router.lock().await;
+            router.forward.last().map(|entry| entry.location.clone())
+        };
+        if let Some(location) = next {
+            let step = Step::Forward;
+            Self::navigate_with(router, location, Params::new(), step, db, api, event_sender).await
         }
     }
 
-    async fn unmount_current(&mut self, db: &Database, api: &Api) {
-        if let Some(cur) = self.current_mut() {
-            cur.unmount(db.clone(), api.clone()).await;
-        }
+    // a page that fails once mounted goes wherever a failed mount would have gone
+    pub async fn fail(
+        router: &Arc<TkMutex<Router>>,
+        err: anyhow::Error,
+        db: &Database,
+        api: &Api,
+        event_sender: &EventSender,
+    ) {
+        let failed = router.lock().await.peek().clone();
+        let (location, params) = match recover(err) {
+            Ok(location) => (location, Params::new()),
+            Err(err) => (ERROR_ROUTE.to_owned(), error_params(failed, err)),
+        };
+        Self::navigate_with(
+            router,
+            location,
+            params,
+            Step::Replace,
+            db,
+            api,
+            event_sender,
+        )
+        .await
     }
 
-    async fn mount_page(
-        &mut self,
+    // Mounts the page without holding the router so the current one keeps drawing, then
+    // takes the step through the history. A redirect or a failure takes the page's place.
+    // The guards of the route get the first say, before anything is cancelled or unmounted.
+    #[async_recursion]
+    async fn navigate_with(
+        router: &Arc<TkMutex<Router>>,
         location: String,
+        mut params: Params,
+        step: Step,
         db: &Database,
         api: &Api,
         event_sender: &EventSender,
-        initial_params: Params,
-    ) -> MountResult {
-        if let Some(route) = self.routes.get_mut_with_params(&location) {
-            let (page, params) = route.initial_params(initial_params).unpack();
-            let result = page
-                .mount(db.clone(), api.clone(), event_sender.clone(), params)
-                .await;
-            if let Err(err) = result {
-                if let Some(req_err) = err.downcast_ref::<SendRequestError>() {
-                    match req_err {
-                        SendRequestError::ExpiredToken => {
-                            Ok(MountOperation::Redirect(TOKEN_EXPIRED_ROUTE.to_owned()))
-                        }
-
-                        _ => Err(err),
-                    }
-                } else {
-                    Err(err)
-                }
+    ) {
+        let redirects: u8 = params
+            .remove(REDIRECTS_PARAM)
+            .and_then(|redirects| redirects.parse().ok())
+            .unwrap_or(0);
+        if redirects > MAX_REDIRECTS {
+            let err = anyhow::anyhow!("Too many redirects, there's no getting to {}", location);
+            return Self::navigate_with(
+                router,
+                ERROR_ROUTE.to_owned(),
+                error_params(location, err),
+                Step::Replace,
+                db,
+                api,
+                event_sender,
+            )
+            .await;
+        }
+        let (route, target, cancel, keeps_current) = loop {
+            let mut this = router.lock().await;
+            let target = if this.routes.contains_location(&location) {
+                location.clone()
             } else {
-                result
+                NOT_FOUND_ROUTE.clone()
+            };
+            match this.check(&target, db, api) {
+                Verdict::Pass => {}
+                Verdict::Redirect(location) => {
+                    drop(this);
+                    // the guarded location never made it into the history
+                    let step = match step {
+                        Step::Replace => Step::Replace,
+                        _ => Step::Push,
+                    };
+                    let params = redirect_params(redirects + 1);
+                    return Self::navigate_with(
+                        router,
+                        location,
+                        params,
+                        step,
+                        db,
+                        api,
+                        event_sender,
+                    )
+                    .await;
+                }
+            }
+            // a newer navigation takes over from the one still mounting
+            if let Some(navigation) = this.navigation.take() {
+                navigation.cancel.notify_one();
+            }
+            if this.routes.is_taken(&target) {
+                // the cancelled navigation hands the page back as soon as it sees the cancel
+                drop(this);
+                tokio::task::yield_now().await;
+                continue;
             }
-        } else {
-            Ok(MountOperation::Redirect(NOT_FOUND_ROUTE.to_owned()))
+            params.insert("location".to_string(), location.clone());
+            params.insert("origin".to_string(), this.peek().clone());
+            this.keep_snapshot();
+
+            // a page can't stay on the screen while it mounts again
+            let keeps_current = this.routes.route_of(&target) != this.routes.route_of(this.peek());
+            if !keeps_current {
+                this.unmount_current(db, api).await;
+            }
+            let route = match this.routes.take(&target) {
+                Some(route) => route,
+                None => return,
+            };
+            let cancel = Arc::new(Notify::new());
+            this.navigation = Some(Navigation {
+                location: target.clone(),
+                cancel: cancel.clone(),
+                keeps_current,
+            });
+            break (route, target, cancel, keeps_current);
+        };
+
+        let (mut page, params) = route.initial_params(params).unpack();
+        let result = tokio::select! {
+            result = page.mount(db.clone(), api.clone(), event_sender.clone(), params) => Some(result),
+            _ = cancel.notified() => None,
+        };
+
+        let mut this = router.lock().await;
+        let current = this
+            .navigation
+            .as_ref()
+            .is_some_and(|navigation| Arc::ptr_eq(&navigation.cancel, &cancel));
+        let result = match result {
+            Some(result) if current => result,
+            // cancelled or taken over, whatever the mount got done is undone
+            _ => {
+                page.unmount(db.clone(), api.clone()).await;
+                this.routes.restore(&target, page);
+                return;
+            }
+        };
+        this.navigation = None;
+        this.routes.restore(&target, page);
+
+        let result = result.or_else(|err| recover(err).map(MountOperation::Redirect));
+        // there's nothing left to fall back on when the error page fails as well
+        if result.is_err() && target == *ERROR_ROUTE {
+            return;
+        }
+
+        if keeps_current {
+            this.unmount_current(db, api).await;
+        }
+        let returning = matches!(step, Step::Back | Step::Forward);
+        this.step(step, target.clone());
+        if returning {
+            let snapshot = this
+                .history
+                .last_mut()
+                .and_then(|entry| entry.snapshot.take());
+            if let (Some(snapshot), Some(page)) = (snapshot, this.current_mut()) {
+                page.restore(snapshot);
+            }
+        }
+        drop(this);
+
+        let (location, params) = match result {
+            Ok(MountOperation::None) => return,
+            Ok(MountOperation::Redirect(location)) => (location, redirect_params(redirects + 1)),
+            Err(err) => (ERROR_ROUTE.to_owned(), error_params(target, err)),
+        };
+        Self::navigate_with(
+            router,
+            location,
+            params,
+            Step::Replace,
+            db,
+            api,
+            event_sender,
+        )
+        .await
+    }
+
+    async fn unmount_current(&mut self, db: &Database, api: &Api) {
+        if let Some(cur) = self.current_mut() {
+            cur.unmount(db.clone(), api.clone()).await;
         }
     }
 
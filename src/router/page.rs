@@ -1,9 +1,13 @@
 use super::{Api, Database, Event, EventSender, Frame, Operation, Params};
 use anyhow::Result;
 use async_trait::async_trait;
+use std::any::Any;
 use tui::layout::Rect;
 
 pub type MountResult = Result<MountOperation>;
+// whatever a page wants back when the history returns to it, like where the cursor was
+pub type Snapshot = Box<dyn Any + Send + Sync>;
+
 pub enum MountOperation {
     None,
     Redirect(String),
@@ -21,4 +25,15 @@ pub trait Page: Send + Sync {
     async fn unmount(&mut self, db: Database, api: Api);
     fn draw(&mut self, frame: &mut Frame, rect: Rect);
     async fn update(&mut self, event: Event, db: Database, api: Api) -> Operation;
+    // Taken when the page is left. Going back or forward to it hands it to `restore` right
+    // after mounting.
+    fn snapshot(&self) -> Option<Snapshot> {
+        None
+    }
+    fn restore(&mut self, _snapshot: Snapshot) {}
+    // While a text field is open every key is typed into it, the status bar doesn't get to
+    // bind any of them.
+    fn captures_input(&self) -> bool {
+        false
+    }
 }
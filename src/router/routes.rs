@@ -1,11 +1,13 @@
 use super::with_params::RouteWithParamsMap;
-use super::{Page, Params};
+use super::{location, Page, Params};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
 
+// A page is `None` while it's out being mounted, see `take`. Locations are looked up by
+// their path, the query string only ends up in the params.
 pub struct Routes {
-    no_params: HashMap<String, Box<dyn Page>>,
+    no_params: HashMap<String, Option<Box<dyn Page>>>,
     with_params: RouteWithParamsMap,
 }
 
@@ -79,54 +81,90 @@ impl Routes {
     {
         static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\/:\w+").unwrap());
 
-        let pattern = route.clone();
         let page = Box::new(page);
-        let params: Vec<_> = REGEX
-            .find_iter(pattern.as_str())
-            .map(|p| p.as_str())
-            .collect();
-        if params.is_empty() {
-            self.no_params.insert(route, page);
+        if REGEX.is_match(&route) {
+            self.with_params.insert(route, page);
         } else {
-            self.with_params.insert(route, params, page);
+            self.no_params.insert(route, Some(page));
         }
     }
 
-    pub fn get(&self, location: &String) -> Option<&dyn Page> {
-        if let Some(p) = self.no_params.get(location) {
-            Some(p.as_ref())
-        } else if let Some(p) = self.with_params.find(location.clone()) {
-            Some(p.page())
+    pub fn get(&self, location: &str) -> Option<&dyn Page> {
+        let (path, _) = location::split(location);
+        if let Some(p) = self.no_params.get(path) {
+            p.as_deref()
+        } else if let Some(p) = self.with_params.find(path.to_string()) {
+            p.page()
         } else {
             None
         }
     }
 
-    pub fn get_mut(&mut self, location: &String) -> Option<&mut dyn Page> {
-        if let Some(p) = self.no_params.get_mut(location) {
-            Some(p.as_mut())
-        } else if let Some(p) = self.with_params.find_mut(location.clone()) {
-            Some(p.page_mut())
+    pub fn get_mut(&mut self, location: &str) -> Option<&mut dyn Page> {
+        let (path, _) = location::split(location);
+        if let Some(p) = self.no_params.get_mut(path) {
+            match p {
+                Some(p) => Some(p.as_mut()),
+                None => None,
+            }
+        } else if let Some(p) = self.with_params.find_mut(path.to_string()) {
+            p.page_mut()
         } else {
             None
         }
     }
 
-    pub fn get_mut_with_params(&mut self, location: &String) -> Option<Route<&mut dyn Page>> {
-        if let Some(p) = self.no_params.get_mut(location) {
-            Some(Route::no_params(p.as_mut()))
-        } else if let Some((p, params)) = self.with_params.find_with_params(location.clone()) {
-            Some(Route::with_params(p.page_mut(), params))
+    // Takes the page out so it can be mounted without holding the router, it's missing from
+    // the routes until `restore` hands it back. The params of the path win over the query's.
+    pub fn take(&mut self, location: &str) -> Option<Route<Box<dyn Page>>> {
+        let (path, query) = location::split(location);
+        let query = query.map(location::parse_query).unwrap_or_default();
+        if let Some(p) = self.no_params.get_mut(path) {
+            p.take()
+                .map(|page| Route::no_params(page).initial_params(query))
+        } else if let Some((p, params)) = self.with_params.find_with_params(path.to_string()) {
+            p.take_page()
+                .map(|page| Route::with_params(page, params).initial_params(query))
         } else {
             None
         }
     }
 
-    pub fn contains_location(&self, location: &String) -> bool {
-        if self.no_params.contains_key(location) {
+    pub fn restore(&mut self, location: &str, page: Box<dyn Page>) {
+        let (path, _) = location::split(location);
+        if let Some(p) = self.no_params.get_mut(path) {
+            *p = Some(page);
+        } else if let Some((p, _)) = self.with_params.find_with_params(path.to_string()) {
+            p.restore_page(page);
+        }
+    }
+
+    pub fn is_taken(&mut self, location: &str) -> bool {
+        let (path, _) = location::split(location);
+        if let Some(p) = self.no_params.get(path) {
+            p.is_none()
+        } else if let Some((p, _)) = self.with_params.find_with_params(path.to_string()) {
+            p.page().is_none()
+        } else {
+            false
+        }
+    }
+
+    // the route a location goes to, two locations with the same route share a page
+    pub fn route_of(&self, location: &str) -> Option<&String> {
+        let (path, _) = location::split(location);
+        self.no_params
+            .get_key_value(path)
+            .map(|(route, _)| route)
+            .or_else(|| self.with_params.route_of(path))
+    }
+
+    pub fn contains_location(&self, location: &str) -> bool {
+        let (path, _) = location::split(location);
+        if self.no_params.contains_key(path) {
             true
         } else {
-            self.with_params.contains_location(location)
+            self.with_params.contains_location(&path.to_string())
         }
     }
 }
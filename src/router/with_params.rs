@@ -1,4 +1,4 @@
-use super::{Page, Params};
+use super::{location, Page, Params};
 use regex::Regex;
 use std::collections::HashSet;
 
@@ -6,7 +6,8 @@ pub struct RouteWithParams {
     route: String,
     regex: Regex,
     parts: Vec<String>,
-    page: Box<dyn Page>,
+    // `None` while the page is out being mounted
+    page: Option<Box<dyn Page>>,
 }
 
 fn split_route(route: &str) -> Vec<String> {
@@ -18,21 +19,30 @@ fn split_route(route: &str) -> Vec<String> {
 }
 
 impl RouteWithParams {
-    pub fn new(route: String, params: Vec<&str>, page: Box<dyn Page>) -> Self {
-        let pattern = params
-            .iter()
-            .fold(route.clone(), |acc, p| acc.replace(p, r"\/.+"));
+    pub fn new(route: String, page: Box<dyn Page>) -> Self {
         let parts = split_route(&route);
+        // a param takes exactly one segment, the encoded `/` of its value included
+        let pattern: String = parts
+            .iter()
+            .map(|part| {
+                if part.starts_with(':') {
+                    "/[^/]+".to_string()
+                } else {
+                    format!("/{}", regex::escape(part))
+                }
+            })
+            .collect();
+        let pattern = format!("^{}/?$", pattern);
         Self {
             route,
             regex: Regex::new(&pattern).unwrap(),
-            page,
+            page: Some(page),
             parts,
         }
     }
 
     pub fn is_match(&self, route: &str) -> bool {
-        self.regex.is_match(route)
+        self.regex.is_match(route) && self.match_params(route).is_some()
     }
 
     pub fn match_params(&self, route: &str) -> Option<Params> {
@@ -45,7 +55,7 @@ impl RouteWithParams {
             |mut acc, (lhs, rhs)| {
                 let mut lhs_iter = lhs.chars();
                 if lhs_iter.next().unwrap_or('\0') == ':' {
-                    acc.insert(lhs_iter.collect(), rhs);
+                    acc.insert(lhs_iter.collect(), location::decode(&rhs).ok_or(())?);
                     Ok(acc)
                 } else if *lhs == rhs {
                     Ok(acc)
@@ -60,12 +70,27 @@ impl RouteWithParams {
         }
     }
 
-    pub fn page(&self) -> &dyn Page {
-        self.page.as_ref()
+    pub fn route(&self) -> &String {
+        &self.route
     }
 
-    pub fn page_mut(&mut self) -> &mut dyn Page {
-        self.page.as_mut()
+    pub fn page(&self) -> Option<&dyn Page> {
+        self.page.as_deref()
+    }
+
+    pub fn page_mut(&mut self) -> Option<&mut dyn Page> {
+        match &mut self.page {
+            Some(page) => Some(page.as_mut()),
+            None => None,
+        }
+    }
+
+    pub fn take_page(&mut self) -> Option<Box<dyn Page>> {
+        self.page.take()
+    }
+
+    pub fn restore_page(&mut self, page: Box<dyn Page>) {
+        self.page = Some(page);
     }
 }
 
@@ -82,9 +107,8 @@ impl RouteWithParamsMap {
         }
     }
 
-    pub fn insert(&mut self, route: String, params: Vec<&str>, page: Box<dyn Page>) {
-        self.routes
-            .push(RouteWithParams::new(route.clone(), params, page));
+    pub fn insert(&mut self, route: String, page: Box<dyn Page>) {
+        self.routes.push(RouteWithParams::new(route.clone(), page));
         self.raw_routes.insert(route);
     }
 
@@ -108,4 +132,11 @@ impl RouteWithParamsMap {
     pub fn contains_location(&self, location: &String) -> bool {
         self.routes.iter().find(|x| x.is_match(&location)).is_some()
     }
+
+    pub fn route_of(&self, location: &str) -> Option<&String> {
+        self.routes
+            .iter()
+            .find(|r| r.match_params(location).is_some())
+            .map(RouteWithParams::route)
+    }
 }
@@ -1,8 +1,9 @@
 use super::Router;
 use crate::api::Api;
 use crate::database::Database;
-use crate::input::EventSender;
+use crate::input::{Event, EventSender};
 use crate::ui::misc::{loading::Loading, status_bar::StatusBar};
+use std::collections::VecDeque;
 use std::io;
 use std::sync::{Arc, Mutex};
 use tokio::sync::Mutex as TkMutex;
@@ -18,6 +19,8 @@ pub struct Context<'a> {
     pub status_bar: StatusBar<'a>,
     pub loading: Loading,
     pub event_sender: EventSender,
+    // what came in for the pages while a navigation was going on, see `ui::update`
+    pub deferred: VecDeque<Event>,
 }
 
 impl<'a> Context<'a> {
@@ -38,6 +41,7 @@ impl<'a> Context<'a> {
             event_sender,
             status_bar,
             loading,
+            deferred: VecDeque::new(),
         }
     }
 
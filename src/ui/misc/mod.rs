@@ -1,4 +1,5 @@
 pub mod layout;
 pub mod loading;
 pub mod logo;
+pub mod skeleton;
 pub mod status_bar;
@@ -0,0 +1,24 @@
+use tui::{
+    style::{Color, Style},
+    text::Span,
+    widgets::ListItem,
+};
+
+const BLOCK: &str = "░";
+// different lengths so the rows read like names
+const WIDTHS: [usize; 5] = [14, 9, 17, 11, 6];
+
+fn bar(width: usize) -> Span<'static> {
+    Span::styled(BLOCK.repeat(width), Style::default().fg(Color::DarkGray))
+}
+
+// placeholder rows drawn where the items go until they're loaded
+pub fn items(count: usize) -> Vec<ListItem<'static>> {
+    (0..count)
+        .map(|index| ListItem::new(bar(WIDTHS[index % WIDTHS.len()])))
+        .collect()
+}
+
+pub fn title() -> Span<'static> {
+    bar(WIDTHS[1])
+}
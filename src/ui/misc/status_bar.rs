@@ -1,4 +1,4 @@
-use crate::input::{Event, IntoInput, KeyCode};
+use crate::input::{Event, IntoInput, KeyCode, KeyModifiers};
 use crate::ui::Operation;
 use crate::ui::{Api, Database, Frame};
 use crate::DARK_MODE;
@@ -7,9 +7,11 @@ use tui::{
     style::{Color, Style},
     widgets::{Block, Paragraph},
 };
+use std::time::Duration;
 use tui_textarea::TextArea;
 
 const HELP_LABEL_TEXT: &str = "Write :help to get help";
+const READ_ONLY_LABEL_TEXT: &str = "[read-only] ";
 const PRIMARY_LABEL_TEXT: &str = "Trellminal {version}";
 
 const BG_COLOR: Color = Color::White;
@@ -54,9 +56,14 @@ impl<'a> StatusBar<'a> {
         let username = {
             let db = db.lock().unwrap();
             let active_account = db.active_account();
-            match active_account {
+            let username = match active_account {
                 Some(account) => account.username.clone(),
                 None => "".to_string(),
+            };
+            if db.is_read_only() {
+                format!("{}{}", READ_ONLY_LABEL_TEXT, username)
+            } else {
+                username
             }
         };
         let layout = Layout::default()
@@ -70,7 +77,34 @@ impl<'a> StatusBar<'a> {
         let username = Paragraph::new(format!("{} | {}", HELP_LABEL_TEXT, username))
             .style(Style::default().fg(fg));
 
-        let text = Paragraph::new(PRIMARY_LABEL_TEXT).style(Style::default().fg(fg));
+        let warning = db.lock().unwrap().warning().cloned();
+        let (cache_status, outbox) = {
+            let api = api.lock().unwrap();
+            (api.cache().status(), api.outbox())
+        };
+        let mut primary_text = PRIMARY_LABEL_TEXT.to_string();
+        match cache_status {
+            Some(status) if status.offline => primary_text.push_str(&format!(
+                " | offline, cached {} ago",
+                format_age(status.age)
+            )),
+            Some(status) => {
+                primary_text.push_str(&format!(" | cached {} ago", format_age(status.age)))
+            }
+            None => {}
+        }
+        if outbox.conflict().is_some() {
+            primary_text.push_str(" | conflict, write :outbox to resolve");
+        } else if outbox.len() > 0 {
+            primary_text.push_str(&format!(" | {} pending", outbox.len()));
+        }
+        if let Some(warning) = outbox.warning() {
+            primary_text.push_str(&format!(" | {}", warning));
+        }
+        if let Some(warning) = warning {
+            primary_text.push_str(&format!(" | {}", warning));
+        }
+        let text = Paragraph::new(primary_text).style(Style::default().fg(fg));
         frame.render_widget(text, layout[0]);
         frame.render_widget(username, layout[1]);
     }
@@ -118,6 +152,14 @@ impl<'a> StatusBar<'a> {
                     self.textarea.input(key_event.clone().into_input());
                     Operation::Consume
                 }
+                // jumps through the history like vim's jump list, never while a page takes text.
+                // terminals that send Ctrl-i as a plain Tab leave `:forward` to jump forward
+                KeyCode::Char('o') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Operation::NavigateBackward
+                }
+                KeyCode::Char('i') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Operation::NavigateForward
+                }
                 _ => Operation::None,
             },
             _ => Operation::None,
@@ -133,6 +175,8 @@ impl<'a> StatusBar<'a> {
         match &*command {
             "q" | "qa" | "q!" => Operation::Exit,
             "back" => Operation::NavigateBackward,
+            "forward" => Operation::NavigateForward,
+            "outbox" => Operation::Navigate("/outbox".to_string()),
             "help" => Operation::Navigate("/help".to_string()),
             _ => Operation::Consume,
         }
@@ -145,3 +189,16 @@ impl<'a> StatusBar<'a> {
         self.input = value;
     }
 }
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 60 * 60 {
+        format!("{}m", secs / 60)
+    } else if secs < 60 * 60 * 24 {
+        format!("{}h", secs / (60 * 60))
+    } else {
+        format!("{}d", secs / (60 * 60 * 24))
+    }
+}
@@ -4,8 +4,9 @@ mod pages;
 
 use crate::api::Api as RawApi;
 use crate::database::Database as RawDatabase;
-use crate::input::{Event, EventSender};
-use crate::router::{Operation, Router};
+use crate::input::{Event, EventSender, KeyCode};
+use crate::models::{BoardId, OrganizationId};
+use crate::router::{encode, guard::requires_account, Operation, Router};
 use context::Context;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
@@ -22,9 +23,10 @@ use tui::{backend::CrosstermBackend, layout, widgets, Frame as TFrame, Terminal}
 use misc::{loading::Loading, status_bar::StatusBar};
 use pages::{
     authenticate::Authenticate, board::Board, boards::Boards,
-    browser_authenticate::BrowserAuthenticate, first_load::FirstLoad, home::Home,
-    manual_authenticate::ManualAuthenticate, not_found::NotFound, token_expired::TokenExpired,
-    workspaces::Workspaces,
+    browser_authenticate::BrowserAuthenticate, database_unreadable::DatabaseUnreadable,
+    error::Error as ErrorPage, first_load::FirstLoad, home::Home,
+    manual_authenticate::ManualAuthenticate, not_found::NotFound, pending_changes::PendingChanges,
+    token_expired::TokenExpired, workspaces::Workspaces,
 };
 
 type Frame<'a> = TFrame<'a, CrosstermBackend<Stdout>>;
@@ -32,6 +34,15 @@ type Frame<'a> = TFrame<'a, CrosstermBackend<Stdout>>;
 type Database = Arc<Mutex<RawDatabase>>;
 type Api = Arc<Mutex<RawApi>>;
 
+// the locations of the pages that take ids, typed so one kind of id can't stand in for another
+pub fn workspace_boards_location(id: &OrganizationId) -> String {
+    format!("/w/{}/boards", encode(id.as_str()))
+}
+
+pub fn board_location(id: &BoardId, name: &str) -> String {
+    format!("/b/{}/{}", encode(id.as_str()), encode(name))
+}
+
 pub async fn init<'a>(
     db: RawDatabase,
     api: RawApi,
@@ -45,9 +56,11 @@ pub async fn init<'a>(
     let terminal = Terminal::new(backend)?;
     let router = Router::new()
         .not_found(NotFound::new())
-        .route("/".to_string(), Home::new())
+        .error(ErrorPage::new())
+        .guarded("/".to_string(), Home::new(), &[requires_account])
         .route("/token_expired".to_string(), TokenExpired::new())
         .route("/first_load".to_string(), FirstLoad::new())
+        .route("/database_unreadable".to_string(), DatabaseUnreadable::new())
         .route("/authenticate".to_string(), Authenticate::new())
         .route(
             "/authenticate/browser".to_string(),
@@ -57,9 +70,22 @@ pub async fn init<'a>(
             "/authenticate/manual".to_string(),
             ManualAuthenticate::new(),
         )
-        .route("/w".to_string(), Workspaces::new())
-        .route("/w/:w/boards".to_string(), Boards::new())
-        .route("/b/:id/:name".to_string(), Board::new());
+        .guarded("/w".to_string(), Workspaces::new(), &[requires_account])
+        .guarded(
+            "/w/:w/boards".to_string(),
+            Boards::new(),
+            &[requires_account],
+        )
+        .guarded(
+            "/b/:id/:name".to_string(),
+            Board::new(),
+            &[requires_account],
+        )
+        .guarded(
+            "/outbox".to_string(),
+            PendingChanges::new(),
+            &[requires_account],
+        );
     let context = Context::new(
         terminal,
         db,
@@ -69,17 +95,14 @@ pub async fn init<'a>(
         StatusBar::new(),
         Loading::braille(10f64),
     );
-    context
-        .router
-        .lock()
-        .await
-        .navigate(
-            String::from(initial_route),
-            &context.db,
-            &context.api,
-            &context.event_sender,
-        )
-        .await;
+    Router::navigate(
+        &context.router,
+        String::from(initial_route),
+        &context.db,
+        &context.api,
+        &context.event_sender,
+    )
+    .await;
     Ok(context)
 }
 
@@ -116,86 +139,60 @@ async fn handle_status_bar_update<'a>(
     operation: Operation,
 ) -> StatusBarUpdateResult {
     match operation {
-        Operation::Navigate(loc) => {
-            tokio::spawn({
-                let db = context.db.clone();
-                let api = context.api.clone();
-                let router = context.router.clone();
-                let event_sender = context.event_sender.clone();
-                async move {
-                    router
-                        .lock()
-                        .await
-                        .navigate(loc, &db, &api, &event_sender)
-                        .await;
-                }
-            });
-            StatusBarUpdateResult::consume()
-        }
-        Operation::NavigateBackward => {
-            tokio::spawn({
-                let db = context.db.clone();
-                let api = context.api.clone();
-                let router = context.router.clone();
-                let event_sender = context.event_sender.clone();
-                async move {
-                    router
-                        .lock()
-                        .await
-                        .navigate_backward(&db, &api, &event_sender)
-                        .await;
-                }
-            });
-            StatusBarUpdateResult::consume()
-        }
         Operation::Consume => StatusBarUpdateResult::consume(),
         Operation::Exit => StatusBarUpdateResult::exit(),
         Operation::None => StatusBarUpdateResult::pass(),
+        navigation => {
+            spawn_navigation(context, navigation);
+            StatusBarUpdateResult::consume()
+        }
     }
 }
 
 async fn handle_page_update<'a>(context: &mut Context<'a>, operation: Operation) -> bool {
     match operation {
-        Operation::Navigate(loc) => {
-            tokio::spawn({
-                let db = context.db.clone();
-                let api = context.api.clone();
-                let router = context.router.clone();
-                let event_sender = context.event_sender.clone();
-                async move {
-                    router
-                        .lock()
-                        .await
-                        .navigate(loc, &db, &api, &event_sender)
-                        .await;
-                }
-            });
-            true
-        }
-        Operation::NavigateBackward => {
-            tokio::spawn({
-                let db = context.db.clone();
-                let api = context.api.clone();
-                let router = context.router.clone();
-                let event_sender = context.event_sender.clone();
-                async move {
-                    router
-                        .lock()
-                        .await
-                        .navigate_backward(&db, &api, &event_sender)
-                        .await;
-                }
-            });
-            true
-        }
         Operation::Exit => false,
         Operation::Consume => true,
         Operation::None => true,
+        navigation => {
+            spawn_navigation(context, navigation);
+            true
+        }
     }
 }
 
+// the page mounts in the background so the current one keeps drawing meanwhile
+fn spawn_navigation(context: &Context, navigation: Operation) {
+    let db = context.db.clone();
+    let api = context.api.clone();
+    let router = context.router.clone();
+    let event_sender = context.event_sender.clone();
+    tokio::spawn(async move {
+        match navigation {
+            Operation::Navigate(loc) => {
+                Router::navigate(&router, loc, &db, &api, &event_sender).await
+            }
+            Operation::NavigateBackward => {
+                Router::navigate_backward(&router, &db, &api, &event_sender).await
+            }
+            Operation::NavigateForward => {
+                Router::navigate_forward(&router, &db, &api, &event_sender).await
+            }
+            Operation::Fail(err) => Router::fail(&router, err, &db, &api, &event_sender).await,
+            Operation::Exit | Operation::Consume | Operation::None => {}
+        }
+    });
+}
+
 pub async fn update<'a>(context: &mut Context<'a>, event: Event) -> Result<bool, Box<dyn Error>> {
-    let status_update = {
+    // a page that's taking text gets the keys before the status bar could bind them
+    let capturing = context
+        .router
+        .try_lock()
+        .is_ok_and(|router| router.current().is_some_and(|page| page.captures_input()));
+    let status_update = if capturing {
+        Operation::None
+    } else {
         context
             .status_bar
             .update(&event, context.db.clone(), context.api.clone())
@@ -204,19 +201,57 @@ pub async fn update<'a>(context: &mut Context<'a>, event: Event) -> Result<bool,
     let status_update_result = { handle_status_bar_update(context, status_update).await };
 
     if status_update_result.consumed {
-        Ok(!status_update_result.exit_requested)
-    } else {
-        let update = if let Ok(mut router) = context.router.try_lock() {
-            router
-                .current_mut()
-                .unwrap()
-                .update(event, context.db.clone(), context.api.clone())
-                .await
-        } else {
-            Operation::None
+        return Ok(!status_update_result.exit_requested);
+    }
+    let router = context.router.clone();
+    let mut router = match router.try_lock() {
+        Ok(router) => router,
+        Err(_) if is_deferred(&event) => {
+            context.deferred.push_back(event);
+            return Ok(true);
+        }
+        Err(_) => return Ok(true),
+    };
+    // The page being mounted isn't current yet and the one on the screen may be on its way out,
+    // neither would make anything of what comes in for the other. It waits for the step.
+    if router.navigating().is_some() && is_deferred(&event) {
+        context.deferred.push_back(event);
+        return Ok(true);
+    }
+    let mut events: Vec<Event> = context.deferred.drain(..).collect();
+    events.push(event);
+    let mut operations = Vec::new();
+    for event in events {
+        let operation = match event {
+            // Esc first goes to giving up on a slow navigation
+            Event::Input(key) if key.code == KeyCode::Esc && router.cancel_navigation() => {
+                Operation::Consume
+            }
+            event => match router.current_mut() {
+                Some(page) => {
+                    page.update(event, context.db.clone(), context.api.clone())
+                        .await
+                }
+                None => Operation::None,
+            },
         };
-        Ok(handle_page_update(context, update).await)
+        operations.push(operation);
     }
+    drop(router);
+    for operation in operations {
+        if !handle_page_update(context, operation).await {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+// meant for whichever page ends up current, as opposed to keys and ticks that are about now
+fn is_deferred(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::CacheRefreshed(_) | Event::CacheFailed(..) | Event::Request(_)
+    )
 }
 
 pub async fn draw<'a>(context: &mut Context<'a>) -> Result<(), Box<dyn Error>> {
@@ -229,6 +264,12 @@ pub async fn draw<'a>(context: &mut Context<'a>) -> Result<(), Box<dyn Error>> {
         if let Ok(mut router) = router {
             if let Some(page) = router.current_mut() {
                 page.draw(frame, layout[0]);
+                if let Some(location) = router.navigating() {
+                    draw_navigating(frame, layout[0], &mut context.loading, location);
+                }
+            } else if router.navigating().is_some() {
+                // the page on the screen is the one mounting again
+                draw_loading(frame, layout[0], &mut context.loading);
             } else {
                 err = "Router is pointing to nowhere";
             }
@@ -266,6 +307,19 @@ fn draw_loading(frame: &mut Frame, rect: layout::Rect, loading: &mut Loading) {
     frame.render_widget(text, layout[1]);
 }
 
+// a line over the bottom of the page that stays up while the next one mounts
+fn draw_navigating(frame: &mut Frame, rect: layout::Rect, loading: &mut Loading, location: &str) {
+    let bottom = rect.y + rect.height.saturating_sub(1);
+    let line = layout::Rect::new(rect.x, bottom, rect.width, 1);
+    let text = widgets::Paragraph::new(format!(
+        " {} Opening {}, [Esc] to stay here ",
+        loading.next(&line),
+        location
+    ))
+    .alignment(layout::Alignment::Center);
+    frame.render_widget(text, line);
+}
+
 pub fn fini(context: &mut Context) -> Result<(), Box<dyn Error>> {
     disable_raw_mode()?;
     execute!(
@@ -11,10 +11,12 @@ use crate::router::{
 };
 use crate::ui::{misc::logo, Api, Database, Frame, Operation};
 
-const MENU_BUTTON_LEN: u8 = 3;
+const MENU_BUTTON_LEN: u8 = 4;
 
 pub struct Authenticate {
     selected_button: u8,
+    // nothing added from here reaches the file, the user gets told before picking a method
+    read_only: bool,
 }
 
 use async_trait::async_trait;
@@ -27,6 +29,7 @@ impl Page for Authenticate {
         event_sender: EventSender,
         params: Params,
     ) -> MountResult {
+        self.read_only = db.lock().unwrap().is_read_only();
         Ok(MountOperation::None)
     }
 
@@ -61,6 +64,7 @@ impl Page for Authenticate {
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
+                Constraint::Length(1),
             ])
             .split(center_layout[3]);
 
@@ -93,6 +97,13 @@ impl Page for Authenticate {
             ),
             (
                 2,
+                Paragraph::new("<Use [l]ocal boards, no Trello>")
+                    .block(Block::default())
+                    .wrap(Wrap { trim: true })
+                    .alignment(Alignment::Center),
+            ),
+            (
+                3,
                 Paragraph::new("<Can[c]el>")
                     .block(Block::default())
                     .wrap(Wrap { trim: true })
@@ -111,10 +122,21 @@ impl Page for Authenticate {
         frame.render_widget(block, rect);
         frame.render_widget(logo, center_layout[0]);
         frame.render_widget(title, center_layout[1]);
+        if self.read_only {
+            let warning = Paragraph::new(
+                "The database is read-only, an account added now is gone once Trellminal quits",
+            )
+            .block(Block::default())
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Red));
+            frame.render_widget(warning, center_layout[2]);
+        }
         frame.render_widget(text, btn_layout[0]);
         frame.render_widget(btn_iter.next().unwrap(), btn_layout[1]);
         frame.render_widget(btn_iter.next().unwrap(), btn_layout[2]);
         frame.render_widget(btn_iter.next().unwrap(), btn_layout[3]);
+        frame.render_widget(btn_iter.next().unwrap(), btn_layout[4]);
     }
 
     async fn update(&mut self, event: Event, db: Database, api: Api) -> Operation {
@@ -122,6 +144,7 @@ impl Page for Authenticate {
             Event::Input(event) => match event.code {
                 KeyCode::Char('a') => Operation::Navigate(String::from("/authenticate/browser")),
                 KeyCode::Char('m') => Operation::Navigate(String::from("/authenticate/manual")),
+                KeyCode::Char('l') => self.use_local(db, api),
                 KeyCode::Char('c') => Operation::Navigate(String::from("/")),
                 KeyCode::Up | KeyCode::Char('k') => {
                     self.menu_up();
@@ -134,7 +157,8 @@ impl Page for Authenticate {
                 KeyCode::Enter => match self.selected_button {
                     0 => Operation::Navigate(String::from("/authenticate/browser")),
                     1 => Operation::Navigate(String::from("/authenticate/manual")),
-                    2 => Operation::Navigate(String::from("/")),
+                    2 => self.use_local(db, api),
+                    3 => Operation::Navigate(String::from("/")),
                     _ => Operation::None,
                 },
                 _ => Operation::None,
@@ -146,7 +170,29 @@ impl Page for Authenticate {
 
 impl Authenticate {
     pub fn new() -> Self {
-        Self { selected_button: 0 }
+        Self {
+            selected_button: 0,
+            read_only: false,
+        }
+    }
+
+    // signs into the local pseudo-account, its boards never leave the machine
+    fn use_local(&self, db: Database, api: Api) -> Operation {
+        let account = {
+            // lock db
+            let mut db = db.lock().unwrap();
+            let account = db.add_local_account();
+            db.set_active_account(account.clone()).unwrap();
+            db.set_first_load(false);
+            account
+        }; // release db
+        {
+            // lock api
+            let mut api = api.lock().unwrap();
+            api.use_local(account.clone());
+            api.set_account(Some(account));
+        } // release api
+        Operation::Navigate(String::from("/"))
     }
 
     fn menu_up(&mut self) -> bool {
@@ -1,22 +1,40 @@
 use itertools::izip;
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 use substring::Substring;
+use tokio::task::JoinHandle;
 use tui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::{Span, Spans, Text},
-    widgets::{Block, Borders, List, ListItem, ListState, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Wrap},
 };
+use tui_textarea::TextArea;
 use unicode_width::UnicodeWidthStr;
 
-use crate::api::boards::Boards;
-use crate::input::{Event, EventSender, KeyCode};
-use crate::models::{BoardId, Card as CardModel, List as ListModel, ListId};
+use crate::cache::keys;
+use crate::database::WebhookConfig;
+use crate::input::{
+    http_server::{HttpServer, Request, STATUS_UNAUTHORIZED},
+    webhook::{self, Payload},
+    Event, EventSender, KeyCode,
+};
+use crate::models::{
+    Board as BoardModel, BoardContent, BoardId, Card as CardModel, CardId, Label,
+    List as ListModel, ListId, Webhook,
+};
+use crate::outbox::{self, Change};
 use crate::router::{
-    page::{MountOperation, MountResult, Page},
+    page::{MountOperation, MountResult, Page, Snapshot},
     Params,
 };
-use crate::ui::{Api, Database, Frame, Operation};
+use crate::ui::{
+    misc::{layout::center_rect, skeleton},
+    workspace_boards_location, Api, Database, Frame, Operation,
+};
+use crate::Ignore;
 
 enum WrapMode {
     TRUNCATE,
@@ -25,14 +43,45 @@ enum WrapMode {
 
 const WRAP_MODE: WrapMode = WrapMode::WRAP;
 
+const POLL_RATE: Duration = Duration::from_secs(15);
+
+// cards created locally have no id until the outbox gets them to Trello
+const PENDING_ID_PREFIX: &str = "pending-";
+
+enum Dialog {
+    AddCard,
+    EditCard,
+    Comment,
+}
+
 pub struct Board {
-    id: BoardId,
+    // set once mounted
+    id: Option<BoardId>,
     name: String,
+    // what came along with the lists and cards, kept so the cached copy stays whole
+    board: Option<BoardModel>,
+    labels: Vec<Label>,
     lists: Vec<ListModel>,
     cards: HashMap<ListId, Vec<CardModel>>,
     selected_list: usize,
     states: Vec<ListState>,
     state: ListState,
+    dialog: Option<Dialog>,
+    textarea: TextArea<'static>,
+    // cards that changed remotely in the last refresh
+    changed: HashSet<CardId>,
+    poller: Option<JoinHandle<()>>,
+    // live updates, only when a webhook is configured in the database
+    webhook: Option<WebhookConfig>,
+    web_server: Option<HttpServer>,
+    registration: Option<JoinHandle<Option<Webhook>>>,
+    event_sender: Option<EventSender>,
+}
+
+// where the cursor was, for coming back to the board
+struct Cursor {
+    selected_list: usize,
+    selected: Vec<Option<usize>>,
 }
 
 use async_trait::async_trait;
@@ -45,49 +94,67 @@ impl Page for Board {
         event_sender: EventSender,
         mut params: Params,
     ) -> MountResult {
+        self.board = None;
         self.lists.clear();
+        self.states.clear();
+        self.changed.clear();
+        self.dialog = None;
+        self.selected_list = 0;
         self.state.select(Some(0));
 
-        self.id = params.remove("id").unwrap();
+        let id: BoardId = params.remove("id").unwrap().parse()?;
+        self.id = Some(id.clone());
         self.name = params.remove("name").unwrap();
 
-        let (lists_req, cards_req) = {
+        let (content_req, cache, outbox) = {
             // lock api
             let api = api.lock().unwrap();
-            let lists = api.boards_lists(&self.id);
-            let cards = api.boards_cards(&self.id);
-            (lists, cards)
+            (api.backend().boards_content(&id), api.cache(), api.outbox())
         }; // release api
-        let (lists, cards) = tokio::join!(lists_req.send(), cards_req.send());
-        self.lists = lists.unwrap();
-        self.cards = cards
-            .unwrap()
-            .into_iter()
-            .fold(HashMap::new(), |mut cards, card| {
-                if !cards.contains_key(&card.id_list) {
-                    cards.insert(card.id_list.clone(), Vec::new());
-                }
-                cards.get_mut(&card.id_list).unwrap().push(card);
-                cards
-            });
-        self.states = self
-            .lists
-            .iter()
-            .map(|_| {
-                let mut state = ListState::default();
-                state.select(Some(0));
-                state
-            })
-            .collect();
-        self.selected_list = 0;
+
+        // without a cached copy the board comes in with `Event::CacheRefreshed`
+        if let Some(content) = cache.load(keys::board(&id), content_req, &event_sender) {
+            self.show(content, false, outbox.pending(&id));
+        }
+        self.poller = Some(spawn_poller(id.clone(), api.clone(), event_sender.clone()));
+
+        self.webhook = db.lock().unwrap().webhook.clone();
+        if let Some(config) = &self.webhook {
+            // the server has to be up before registering, Trello checks the url right away
+            self.web_server =
+                HttpServer::bind(event_sender.clone(), config.port, webhook::is_webhook).ok();
+            if self.web_server.is_some() {
+                self.registration = Some(spawn_registration(id, config.callback_url.clone(), api));
+            }
+        }
+        self.event_sender = Some(event_sender);
 
         Ok(MountOperation::None)
     }
 
-    async fn unmount(&mut self, db: Database, api: Api) {}
+    async fn unmount(&mut self, db: Database, api: Api) {
+        if let Some(poller) = self.poller.take() {
+            poller.abort();
+        }
+        if let Some(registration) = self.registration.take() {
+            tokio::spawn(async move {
+                if let Ok(Some(webhook)) = registration.await {
+                    let req = api.lock().unwrap().backend().webhooks_delete(&webhook.id);
+                    req.await.ignore();
+                }
+            });
+        }
+        self.web_server = None;
+        self.event_sender = None;
+    }
 
     fn draw(&mut self, frame: &mut Frame, rect: Rect) {
         let block = Block::default().title("Board").borders(Borders::ALL);
+        if self.board.is_none() {
+            frame.render_widget(block, rect);
+            self.draw_skeleton(frame, rect);
+            return;
+        }
         let coulmn_percent = 100 / self.lists.len() as u16;
 
         let lists_layout = Layout::default()
@@ -111,7 +178,7 @@ impl Page for Board {
         .map(|(data, list, rect)| {
             (
                 data,
-                Board::make_list(list, self.cards.get(&list.id), rect),
+                Board::make_list(list, self.cards.get(&list.id), &self.changed, rect),
                 rect,
             )
         })
@@ -132,10 +199,60 @@ impl Page for Board {
         .for_each(|(index, list, rect)| {
             frame.render_stateful_widget(list, rect, &mut self.states[index])
         });
+
+        if self.dialog.is_some() {
+            self.draw_dialog(frame, rect);
+        }
+    }
+
+    // every dialog is a text field
+    fn captures_input(&self) -> bool {
+        self.dialog.is_some()
+    }
+
+    fn snapshot(&self) -> Option<Snapshot> {
+        Some(Box::new(Cursor {
+            selected_list: self.selected_list,
+            selected: self.states.iter().map(ListState::selected).collect(),
+        }))
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        if let Ok(cursor) = snapshot.downcast::<Cursor>() {
+            self.selected_list = cursor.selected_list;
+            self.states = cursor
+                .selected
+                .iter()
+                .map(|selected| {
+                    let mut state = ListState::default();
+                    state.select(*selected);
+                    state
+                })
+                .collect();
+            // a board still loading gets them fitted once its lists are in
+            if self.board.is_some() {
+                self.fit_states();
+            }
+        }
     }
 
     async fn update(&mut self, event: Event, db: Database, api: Api) -> Operation {
+        // webhook calls can't wait for the dialog to close
+        if let Event::Request(req) = event {
+            self.dispatch_request(req, api);
+            return Operation::None;
+        }
+        if self.dialog.is_some() {
+            return self.dialog_update(event, api);
+        }
         match event {
+            Event::CacheRefreshed(key) => {
+                self.refresh(&key, api);
+                Operation::None
+            }
+            Event::CacheFailed(key, err) if self.is_mine(&key) => Operation::Fail(err.into()),
+            // nothing to move around on until the board is in
+            Event::Input(_) if self.board.is_none() => Operation::None,
             Event::Input(event) => match event.code {
                 KeyCode::Up | KeyCode::Char('k') => {
                     self.up();
@@ -153,10 +270,35 @@ impl Page for Board {
                     self.right();
                     Operation::None
                 }
-                KeyCode::Enter => {
-                    let org_id = self.lists[self.state.selected().unwrap()].id.clone();
-                    Operation::Navigate(format!("/w/{}/boards", org_id))
+                KeyCode::Char('H') => {
+                    self.move_card(-1, &api);
+                    Operation::None
+                }
+                KeyCode::Char('L') => {
+                    self.move_card(1, &api);
+                    Operation::None
+                }
+                KeyCode::Char('a') => {
+                    self.open_dialog(Dialog::AddCard);
+                    Operation::None
                 }
+                KeyCode::Char('e') => {
+                    self.open_dialog(Dialog::EditCard);
+                    Operation::None
+                }
+                KeyCode::Char('c') => {
+                    self.open_dialog(Dialog::Comment);
+                    Operation::None
+                }
+                // back to the boards of the workspace this one belongs to
+                KeyCode::Enter => match self
+                    .lists
+                    .get(self.selected_list)
+                    .and_then(|list| list.id_organization.as_ref())
+                {
+                    Some(org_id) => Operation::Navigate(workspace_boards_location(org_id)),
+                    None => Operation::None,
+                },
                 _ => Operation::None,
             },
             _ => Operation::None,
@@ -167,14 +309,409 @@ impl Page for Board {
 impl Board {
     pub fn new() -> Self {
         Self {
-            id: String::default(),
+            id: None,
             name: String::default(),
+            board: None,
+            labels: Vec::new(),
             lists: Vec::new(),
             cards: HashMap::new(),
             states: Vec::new(),
             state: ListState::default(),
             selected_list: 0,
+            dialog: None,
+            textarea: TextArea::default(),
+            changed: HashSet::new(),
+            poller: None,
+            webhook: None,
+            web_server: None,
+            registration: None,
+            event_sender: None,
+        }
+    }
+
+    fn dispatch_request(&mut self, req: Request, api: Api) {
+        let config = match &self.webhook {
+            Some(config) => config,
+            None => return,
+        };
+        if !webhook::verify(&req, &config.secret, &config.callback_url) {
+            req.respond_with_status(STATUS_UNAUTHORIZED, String::new())
+                .ignore();
+            return;
+        }
+        let payload = webhook::parse(&req);
+        req.respond(String::new()).ignore();
+        let applied = payload.is_some_and(|payload| self.apply(payload));
+        if !applied {
+            if let (Some(id), Some(event_sender)) = (self.id.clone(), self.event_sender.clone()) {
+                tokio::spawn(refresh_board(id, api, event_sender));
+            }
+        }
+    }
+
+    // applies the actions that carry everything needed, the rest go through a refresh
+    fn apply(&mut self, payload: Payload) -> bool {
+        let action = payload.action;
+        let card = match action.data.card {
+            Some(card) => card,
+            None => return false,
+        };
+        let (list_id, index) = match self.cards.iter().find_map(|(list_id, cards)| {
+            cards
+                .iter()
+                .position(|c| c.id == card.id)
+                .map(|index| (list_id.clone(), index))
+        }) {
+            Some(position) => position,
+            None => return false,
+        };
+        let cards = self.cards.get_mut(&list_id).unwrap();
+        match action.kind.as_str() {
+            "deleteCard" => {
+                cards.remove(index);
+            }
+            "updateCard" if card.closed == Some(true) => {
+                cards.remove(index);
+            }
+            "updateCard" => {
+                if let Some(name) = card.name {
+                    cards[index].name = name;
+                }
+                if let Some(id_list) = card.id_list.filter(|id_list| *id_list != list_id) {
+                    let mut moved = cards.remove(index);
+                    moved.id_list = id_list.clone();
+                    self.cards.entry(id_list).or_default().push(moved);
+                }
+                self.changed = HashSet::from([card.id]);
+            }
+            _ => return false,
+        }
+        self.set_cards(self.cards.values().flatten().cloned().collect());
+        true
+    }
+
+    // whether the cache key is the one of the board shown
+    fn is_mine(&self, key: &str) -> bool {
+        self.id.as_ref().is_some_and(|id| key == keys::board(id))
+    }
+
+    fn selected_card(&self) -> Option<&CardModel> {
+        let list = self.lists.get(self.selected_list)?;
+        let index = self.states[self.selected_list].selected().unwrap_or(0);
+        self.cards.get(&list.id)?.get(index)
+    }
+
+    // only cards that made it to Trello can be changed or commented on
+    fn selected_synced_card(&self) -> Option<&CardModel> {
+        self.selected_card()
+            .filter(|card| !card.id.as_str().starts_with(PENDING_ID_PREFIX))
+    }
+
+    // The change is already on the screen, the outbox delivers it. The cache only ever gets
+    // what Trello sent, the changes still in the outbox go over every copy shown instead.
+    fn queue(&mut self, change: Change, api: &Api) {
+        let outbox = api.lock().unwrap().outbox();
+        outbox.push(change);
+    }
+
+    fn move_card(&mut self, offset: isize, api: &Api) {
+        let target = self.selected_list as isize + offset;
+        if target < 0 || target as usize >= self.lists.len() {
+            return;
+        }
+        let id_board = match self.id.clone() {
+            Some(id) => id,
+            None => return,
+        };
+        let (id, base) = match self.selected_synced_card() {
+            Some(card) => (card.id.clone(), card.date_last_activity.clone()),
+            None => return,
+        };
+        let from = self.lists[self.selected_list].id.clone();
+        let to = self.lists[target as usize].id.clone();
+        let index = self.states[self.selected_list].selected().unwrap_or(0);
+        let mut card = self.cards.get_mut(&from).unwrap().remove(index);
+        card.id_list = to.clone();
+        self.cards.entry(to.clone()).or_default().push(card);
+        let len = self.cards[&from].len();
+        if index >= len {
+            self.states[self.selected_list].select(Some(len.saturating_sub(1)));
+        }
+        let change = Change::UpdateCard {
+            id_board,
+            id,
+            base,
+            name: None,
+            id_list: Some(to),
+        };
+        self.queue(change, api);
+    }
+
+    fn open_dialog(&mut self, dialog: Dialog) {
+        let (title, text) = match dialog {
+            Dialog::AddCard if !self.lists.is_empty() => ("New card", String::new()),
+            Dialog::EditCard => match self.selected_synced_card() {
+                Some(card) => ("Card name", card.name.clone()),
+                None => return,
+            },
+            Dialog::Comment if self.selected_synced_card().is_some() => {
+                ("Comment", String::new())
+            }
+            _ => return,
+        };
+        self.textarea = TextArea::new(vec![text]);
+        self.textarea.move_cursor(tui_textarea::CursorMove::End);
+        self.textarea
+            .set_block(Block::default().title(title).borders(Borders::ALL));
+        self.dialog = Some(dialog);
+    }
+
+    fn dialog_update(&mut self, event: Event, api: Api) -> Operation {
+        match event {
+            Event::Input(key_event) => match key_event.code {
+                KeyCode::Enter => {
+                    let text = self.textarea.lines().join("\n").trim().to_string();
+                    if let Some(dialog) = self.dialog.take() {
+                        if !text.is_empty() {
+                            self.submit_dialog(dialog, text, &api);
+                        }
+                    }
+                    Operation::None
+                }
+                KeyCode::Esc => {
+                    self.dialog = None;
+                    Operation::None
+                }
+                _ => {
+                    self.textarea.input(event);
+                    Operation::None
+                }
+            },
+            _ => Operation::None,
+        }
+    }
+
+    fn submit_dialog(&mut self, dialog: Dialog, text: String, api: &Api) {
+        let id_board = match self.id.clone() {
+            Some(id) => id,
+            None => return,
+        };
+        match dialog {
+            Dialog::AddCard => {
+                let list = &self.lists[self.selected_list];
+                let pending_id = outbox::pending_id();
+                let card = pending_card(list, &pending_id, &text);
+                let change = Change::CreateCard {
+                    id_board,
+                    id_list: list.id.clone(),
+                    name: text,
+                    pending_id,
+                };
+                self.cards.entry(list.id.clone()).or_default().push(card);
+                self.queue(change, api);
+            }
+            Dialog::EditCard => {
+                let list_id = self.lists[self.selected_list].id.clone();
+                let index = self.states[self.selected_list].selected().unwrap_or(0);
+                let card = &mut self.cards.get_mut(&list_id).unwrap()[index];
+                card.name = text.clone();
+                let change = Change::UpdateCard {
+                    id_board,
+                    id: card.id.clone(),
+                    base: card.date_last_activity.clone(),
+                    name: Some(text),
+                    id_list: None,
+                };
+                self.queue(change, api);
+            }
+            Dialog::Comment => {
+                if let Some(card) = self.selected_synced_card() {
+                    let change = Change::Comment {
+                        id_board,
+                        id: card.id.clone(),
+                        text,
+                    };
+                    self.queue(change, api);
+                }
+            }
+        }
+    }
+
+    fn draw_dialog(&self, frame: &mut Frame, rect: Rect) {
+        let dialog_rect = center_rect(rect, std::cmp::min(rect.width, 60));
+        let dialog_rect = Rect::new(
+            dialog_rect.x,
+            dialog_rect.y + dialog_rect.height.saturating_sub(3) / 2,
+            dialog_rect.width,
+            std::cmp::min(dialog_rect.height, 3),
+        );
+        frame.render_widget(Clear, dialog_rect);
+        frame.render_widget(self.textarea.widget(), dialog_rect);
+    }
+
+    fn set_lists(&mut self, lists: Vec<ListModel>) {
+        self.lists = lists;
+        self.fit_states();
+    }
+
+    // keeps the cursor where it was for the lists that are still around
+    fn fit_states(&mut self) {
+        self.states.resize_with(self.lists.len(), || {
+            let mut state = ListState::default();
+            state.select(Some(0));
+            state
+        });
+        if self.selected_list >= self.lists.len() {
+            self.selected_list = self.lists.len().saturating_sub(1);
+        }
+    }
+
+    fn set_cards(&mut self, cards: Vec<CardModel>) {
+        self.cards = cards
+            .into_iter()
+            .fold(HashMap::new(), |mut cards, card| {
+                if !cards.contains_key(&card.id_list) {
+                    cards.insert(card.id_list.clone(), Vec::new());
+                }
+                cards.get_mut(&card.id_list).unwrap().push(card);
+                cards
+            });
+        let lists = &self.lists;
+        let cards = &self.cards;
+        self.states.iter_mut().zip(lists).for_each(|(state, list)| {
+            let len = cards.get(&list.id).map_or(0, |c| c.len());
+            if state.selected().unwrap_or(0) >= len {
+                state.select(Some(len.saturating_sub(1)));
+            }
+        });
+    }
+
+    fn refresh(&mut self, key: &str, api: Api) {
+        let (cache, outbox) = {
+            // lock api
+            let api = api.lock().unwrap();
+            (api.cache(), api.outbox())
+        }; // release api
+        let id = match &self.id {
+            Some(id) if key == keys::board(id) => id.clone(),
+            _ => return,
+        };
+        if let Some(content) = cache.get::<BoardContent>(key) {
+            // the first copy of a board that wasn't cached has nothing to compare against
+            let merge = self.board.is_some();
+            self.show(content, merge, outbox.pending(&id));
+        }
+    }
+
+    fn show(&mut self, content: BoardContent, merge: bool, pending: Vec<Change>) {
+        self.board = Some(content.board);
+        self.labels = content.labels;
+        self.set_lists(content.lists);
+        let cards = self.overlay(content.cards, pending);
+        if merge {
+            self.merge_cards(cards);
+        } else {
+            self.set_cards(cards);
+        }
+    }
+
+    // the changes that are yet to reach Trello, on top of what it sent
+    fn overlay(&self, mut cards: Vec<CardModel>, pending: Vec<Change>) -> Vec<CardModel> {
+        for change in pending {
+            match change {
+                Change::CreateCard {
+                    id_list,
+                    name,
+                    pending_id,
+                    ..
+                } => {
+                    if let Some(list) = self.lists.iter().find(|list| list.id == id_list) {
+                        cards.push(pending_card(list, &pending_id, &name));
+                    }
+                }
+                Change::UpdateCard {
+                    id, name, id_list, ..
+                } => {
+                    if let Some(card) = cards.iter_mut().find(|card| card.id == id) {
+                        if let Some(name) = name {
+                            card.name = name;
+                        }
+                        if let Some(id_list) = id_list {
+                            card.id_list = id_list;
+                        }
+                    }
+                }
+                Change::Comment { .. } => {}
+            }
         }
+        cards
+    }
+
+    // a few empty columns standing in for the lists until they're loaded
+    fn draw_skeleton(&self, frame: &mut Frame, rect: Rect) {
+        let columns = Layout::default()
+            .margin(1)
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 3); 3])
+            .split(rect);
+        for (index, column) in columns.into_iter().enumerate() {
+            let list = List::new(skeleton::items(3 + index * 2)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::DarkGray))
+                    .title(skeleton::title()),
+            );
+            frame.render_widget(list, column);
+        }
+    }
+
+    // replaces the cards with a fresher copy, keeping the cursor on the same cards
+    // and remembering which ones are different from before
+    fn merge_cards(&mut self, cards: Vec<CardModel>) {
+        let previous: HashMap<&CardId, &CardModel> = self
+            .cards
+            .values()
+            .flatten()
+            .map(|card| (&card.id, card))
+            .collect();
+        self.changed = cards
+            .iter()
+            .filter(|card| {
+                previous.get(&card.id).is_none_or(|old| {
+                    old.name != card.name
+                        || old.id_list != card.id_list
+                        || old.date_last_activity != card.date_last_activity
+                })
+            })
+            .map(|card| card.id.clone())
+            .collect();
+        let selected: Vec<Option<CardId>> = self
+            .lists
+            .iter()
+            .zip(&self.states)
+            .map(|(list, state)| {
+                let index = state.selected().unwrap_or(0);
+                self.cards
+                    .get(&list.id)
+                    .and_then(|cards| cards.get(index))
+                    .map(|card| card.id.clone())
+            })
+            .collect();
+
+        self.set_cards(cards);
+
+        let lists = &self.lists;
+        let cards = &self.cards;
+        izip!(self.states.iter_mut(), lists, selected).for_each(|(state, list, selected)| {
+            let index = selected.and_then(|id| {
+                cards
+                    .get(&list.id)
+                    .and_then(|cards| cards.iter().position(|card| card.id == id))
+            });
+            if let Some(index) = index {
+                state.select(Some(index));
+            }
+        });
     }
 
     fn up(&mut self) {
@@ -217,7 +754,12 @@ impl Board {
         }
     }
 
-    fn make_list<'a>(list: &ListModel, cards: Option<&Vec<CardModel>>, rect: Rect) -> List<'a> {
+    fn make_list<'a>(
+        list: &ListModel,
+        cards: Option<&Vec<CardModel>>,
+        changed: &HashSet<CardId>,
+        rect: Rect,
+    ) -> List<'a> {
         let items: Vec<ListItem> = if let Some(cards) = cards {
             cards
                 .iter()
@@ -233,7 +775,11 @@ impl Board {
                         card.name.clone()
                     };
 
-                    ListItem::new(text)
+                    if changed.contains(&card.id) {
+                        ListItem::new(text).style(Style::default().fg(Color::Green))
+                    } else {
+                        ListItem::new(text)
+                    }
                 })
                 .collect()
         } else {
@@ -243,6 +789,66 @@ impl Board {
     }
 }
 
+// Keeps an eye on the board's last activity and pulls the lists and cards again
+// whenever someone else touches it.
+fn spawn_poller(id: BoardId, api: Api, event_sender: EventSender) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last_activity = None;
+        let mut interval = tokio::time::interval(POLL_RATE);
+        loop {
+            interval.tick().await;
+            let req = api.lock().unwrap().backend().boards_get(&id);
+            let activity = match req.await {
+                Ok(board) => board.date_last_activity,
+                Err(_) => continue,
+            };
+            if last_activity.is_none() || activity == last_activity {
+                last_activity = activity;
+                continue;
+            }
+            last_activity = activity;
+            refresh_board(id.clone(), api.clone(), event_sender.clone()).await;
+        }
+    })
+}
+
+async fn refresh_board(id: BoardId, api: Api, event_sender: EventSender) {
+    let (content_req, cache) = {
+        // lock api
+        let api = api.lock().unwrap();
+        (api.backend().boards_content(&id), api.cache())
+    }; // release api
+    cache
+        .refresh(keys::board(&id), content_req, &event_sender)
+        .await;
+}
+
+// stands in for a card that's still in the outbox
+fn pending_card(list: &ListModel, pending_id: &str, name: &str) -> CardModel {
+    CardModel {
+        id: CardId::unchecked(format!("{}{}", PENDING_ID_PREFIX, pending_id)),
+        id_board: list.id_board.clone(),
+        id_list: list.id.clone(),
+        id_organization: list.id_organization.clone(),
+        name: name.to_string(),
+        labels: Vec::new(),
+        url: String::new(),
+        date_last_activity: None,
+    }
+}
+
+// Asks Trello to call us back on every change to the board, the webhook gets deleted
+// again when the page goes away.
+fn spawn_registration(id: BoardId, callback_url: String, api: Api) -> JoinHandle<Option<Webhook>> {
+    tokio::spawn(async move {
+        let backend = api.lock().unwrap().backend();
+        backend
+            .webhooks_create(&callback_url, id.as_str())
+            .await
+            .ok()
+    })
+}
+
 fn text_truncate(text: String, size: usize) -> String {
     format!("{}...", text.as_str().substring(0, size))
 }
@@ -4,22 +4,29 @@ use tui::{
     widgets::{Block, Borders, List, ListItem, ListState},
 };
 
-use crate::api::organizations::Organizations;
+use crate::cache::keys;
 use crate::input::{Event, EventSender, KeyCode};
-use crate::models::Board;
+use crate::models::{Board, OrganizationId};
 use crate::router::{
-    page::{MountOperation, MountResult, Page},
+    page::{MountOperation, MountResult, Page, Snapshot},
     Params,
 };
 use crate::ui::{
-    misc::layout::{center_rect_with_margin, rect_with_margin_top},
+    board_location,
+    misc::{
+        layout::{center_rect_with_margin, rect_with_margin_top},
+        skeleton,
+    },
     Api, Database, Frame, Operation,
 };
 
 use tokio::task::JoinSet;
 
 pub struct Boards {
+    // set once mounted
+    workspace_id: Option<OrganizationId>,
     boards: Vec<Board>,
+    loaded: bool,
     state: ListState,
 }
 
@@ -34,15 +41,24 @@ impl Page for Boards {
         mut params: Params,
     ) -> MountResult {
         self.boards.clear();
+        self.loaded = false;
         self.state.select(Some(0));
 
-        let workspace_id = params.remove("w").unwrap();
-        let boards_req = {
+        let workspace_id: OrganizationId = params.remove("w").unwrap().parse()?;
+        let (boards_req, cache) = {
             // lock api
             let api = api.lock().unwrap();
-            api.organizations_boards(&workspace_id)
+            (
+                api.backend().organizations_boards(&workspace_id),
+                api.cache(),
+            )
         }; // release api
-        self.boards = boards_req.send().await?;
+        let key = keys::organization_boards(&workspace_id);
+        self.workspace_id = Some(workspace_id);
+        if let Some(boards) = cache.load(key, boards_req, &event_sender) {
+            self.boards = boards;
+            self.loaded = true;
+        }
 
         Ok(MountOperation::None)
     }
@@ -55,11 +71,14 @@ impl Page for Boards {
         let list_block_rect = center_rect_with_margin(rect, 30, 1);
         let list_rect = rect_with_margin_top(list_block_rect, 2);
 
-        let recent_boards: Vec<ListItem> = self
-            .boards
-            .iter()
-            .map(|b| ListItem::new(b.name.clone()))
-            .collect();
+        let recent_boards: Vec<ListItem> = if self.loaded {
+            self.boards
+                .iter()
+                .map(|b| ListItem::new(b.name.clone()))
+                .collect()
+        } else {
+            skeleton::items(4)
+        };
 
         let boards_block = Block::default()
             .title("Select a board")
@@ -75,8 +94,31 @@ impl Page for Boards {
         frame.render_stateful_widget(boards_list, list_rect, &mut self.state);
     }
 
+    fn snapshot(&self) -> Option<Snapshot> {
+        Some(Box::new(self.state.selected()))
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        if let Ok(selected) = snapshot.downcast::<Option<usize>>() {
+            self.state.select(*selected);
+            if self.loaded {
+                self.clamp_selection();
+            }
+        }
+    }
+
     async fn update(&mut self, event: Event, db: Database, api: Api) -> Operation {
         match event {
+            Event::CacheRefreshed(key) if self.is_mine(&key) => {
+                let cache = api.lock().unwrap().cache();
+                if let Some(boards) = cache.get(&key) {
+                    self.boards = boards;
+                    self.loaded = true;
+                    self.clamp_selection();
+                }
+                Operation::None
+            }
+            Event::CacheFailed(key, err) if self.is_mine(&key) => Operation::Fail(err.into()),
             Event::Input(event) => match event.code {
                 KeyCode::Up | KeyCode::Char('k') => {
                     self.up();
@@ -86,10 +128,12 @@ impl Page for Boards {
                     self.down();
                     Operation::None
                 }
-                KeyCode::Enter => {
-                    let selected = &self.boards[self.state.selected().unwrap()];
-                    Operation::Navigate(format!("/b/{}/{}", selected.id, selected.name))
-                }
+                KeyCode::Enter => match self.boards.get(self.state.selected().unwrap()) {
+                    Some(selected) => {
+                        Operation::Navigate(board_location(&selected.id, &selected.name))
+                    }
+                    None => Operation::None,
+                },
                 _ => Operation::None,
             },
             _ => Operation::None,
@@ -100,11 +144,20 @@ impl Page for Boards {
 impl Boards {
     pub fn new() -> Self {
         Self {
+            workspace_id: None,
             boards: Vec::new(),
+            loaded: false,
             state: ListState::default(),
         }
     }
 
+    // whether the cache key is the one of the boards shown
+    fn is_mine(&self, key: &str) -> bool {
+        self.workspace_id
+            .as_ref()
+            .is_some_and(|id| key == keys::organization_boards(id))
+    }
+
     pub fn up(&mut self) {
         let current_index = self.state.selected().unwrap_or(0);
         if current_index > 0 {
@@ -118,4 +171,10 @@ impl Boards {
             self.state.select(Some(new_index))
         }
     }
+
+    fn clamp_selection(&mut self) {
+        if self.state.selected().unwrap_or(0) >= self.boards.len() {
+            self.state.select(Some(self.boards.len().saturating_sub(1)));
+        }
+    }
 }
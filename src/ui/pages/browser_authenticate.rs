@@ -4,7 +4,6 @@ use tui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use crate::api::members::Members;
 use crate::{API_KEY, APP_NAME};
 
 use const_format::formatcp;
@@ -27,7 +26,7 @@ pub struct BrowserAuthenticate {
 }
 
 const MENU_BUTTON_LEN: u8 = 3;
-const AUTH_URL: &str = formatcp!("https://trello.com/1/authorize?expiration=1day&name={APP_NAME}&scope=read&response_type=token&key={API_KEY}&return_url=http://127.0.0.1:9999/auth");
+const AUTH_URL: &str = formatcp!("https://trello.com/1/authorize?expiration=1day&name={APP_NAME}&scope=read,write&response_type=token&key={API_KEY}&return_url=http://127.0.0.1:9999/auth");
 
 use async_trait::async_trait;
 const AUTH_ROUTE: &str = "/auth";
@@ -54,7 +53,7 @@ impl Page for BrowserAuthenticate {
         event_sender: EventSender,
         params: Params,
     ) -> MountResult {
-        self.web_server = Some(HttpServer::new(event_sender, "9999", request_validator));
+        self.web_server = Some(HttpServer::new(event_sender, 9999, request_validator));
         Ok(MountOperation::None)
     }
 
@@ -183,7 +182,7 @@ impl Page for BrowserAuthenticate {
                 _ => Operation::None,
             },
             Event::Request(req) => self.dispatch_request(req, db, api).await,
-            Event::Tick => Operation::None,
+            _ => Operation::None,
         }
     }
 }
@@ -211,16 +210,17 @@ impl BrowserAuthenticate {
             let user_req = {
                 let mut api = api.lock().unwrap();
                 api.auth(token.clone());
-                api.members_me()
+                api.backend().members_me()
             }; // unlock api
-            let user = user_req.send().await.unwrap();
+            let user = user_req.await.unwrap();
             let user_id = user.id.clone();
             {
                 let mut db = db.lock().unwrap();
                 db.add_user_account(user, token).unwrap();
-                db.set_active_account(user_id).unwrap();
-                db.first_load = false;
+                db.set_active_account(user_id.clone()).unwrap();
+                db.set_first_load(false);
             } // unlock db
+            api.lock().unwrap().set_account(Some(user_id));
             req.respond_with_html("auth_success.html").unwrap();
             Operation::Navigate("/".to_string())
         } else {
@@ -0,0 +1,252 @@
+use tui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::input::{Event, EventSender, KeyCode};
+use crate::router::{
+    page::{MountOperation, MountResult, Page},
+    Params,
+};
+use crate::ui::{Api, Database, Frame, Operation};
+use std::process::{Command, Stdio};
+
+#[cfg(target_os = "macos")]
+const OPENER: (&str, &[&str]) = ("open", &[]);
+#[cfg(target_os = "windows")]
+const OPENER: (&str, &[&str]) = ("cmd", &["/C", "start", ""]);
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const OPENER: (&str, &[&str]) = ("xdg-open", &[]);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Button {
+    Reset,
+    OpenFile,
+    ReadOnly,
+    Quit,
+}
+
+impl Button {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Reset => "<[R]eset the database (a backup is kept)>",
+            Self::OpenFile => "<[O]pen the database file>",
+            Self::ReadOnly => "<[C]ontinue read-only>",
+            Self::Quit => "<[Q]uit>",
+        }
+    }
+}
+
+// a file that is fine but can't be used by this version is left alone
+const CORRUPT_BUTTONS: &[Button] = &[
+    Button::Reset,
+    Button::OpenFile,
+    Button::ReadOnly,
+    Button::Quit,
+];
+const UNREADABLE_BUTTONS: &[Button] = &[Button::ReadOnly, Button::Quit];
+
+pub struct DatabaseUnreadable {
+    selected_button: u8,
+    buttons: &'static [Button],
+    path: String,
+    error: String,
+    failed_open_file: bool,
+}
+
+use async_trait::async_trait;
+#[async_trait]
+impl Page for DatabaseUnreadable {
+    async fn mount(
+        &mut self,
+        db: Database,
+        api: Api,
+        event_sender: EventSender,
+        params: Params,
+    ) -> MountResult {
+        self.failed_open_file = false;
+        self.selected_button = 0;
+        {
+            // lock db
+            let db = db.lock().unwrap();
+            self.path = db.path().clone();
+            self.error = db.load_error().cloned().unwrap_or_default();
+            self.buttons = if db.is_corrupt() {
+                CORRUPT_BUTTONS
+            } else {
+                UNREADABLE_BUTTONS
+            };
+        } // release db
+        Ok(MountOperation::None)
+    }
+
+    async fn unmount(&mut self, db: Database, api: Api) {}
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let block = Block::default()
+            .title("Database Unreadable")
+            .borders(Borders::ALL);
+        let main_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(10),
+                Constraint::Percentage(80),
+                Constraint::Percentage(10),
+            ])
+            .split(rect);
+        let center_rect = main_layout[1];
+        let center_layout = Layout::default()
+            .margin(1)
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Length(1),
+                Constraint::Min(2),
+                Constraint::Length(1),
+                Constraint::Length(4),
+                Constraint::Percentage(10),
+            ])
+            .split(center_rect);
+        let btn_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(center_layout[4]);
+
+        let title = Paragraph::new(format!("Failed to read the database at \"{}\"", self.path))
+            .block(Block::default())
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center);
+
+        let msg = Paragraph::new(self.error.clone())
+            .block(Block::default())
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Red));
+
+        let btns = self.buttons.iter().enumerate().map(|(index, button)| {
+            let btn = Paragraph::new(button.label())
+                .block(Block::default())
+                .wrap(Wrap { trim: true })
+                .alignment(Alignment::Center);
+            if index as u8 == self.selected_button {
+                btn.style(Style::default().fg(Color::Yellow))
+            } else {
+                btn
+            }
+        });
+
+        frame.render_widget(block, rect);
+        frame.render_widget(title, center_layout[1]);
+        frame.render_widget(msg, center_layout[2]);
+        if self.failed_open_file {
+            let open_error = Paragraph::new("Failed to open the file, you can open it manually!")
+                .block(Block::default())
+                .wrap(Wrap { trim: true })
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Red));
+            frame.render_widget(open_error, center_layout[3]);
+        }
+        for (btn, rect) in btns.zip(btn_layout) {
+            frame.render_widget(btn, rect);
+        }
+    }
+
+    async fn update(&mut self, event: Event, db: Database, api: Api) -> Operation {
+        match event {
+            Event::Input(event) => match event.code {
+                KeyCode::Char('r') | KeyCode::Char('R') => self.press(Button::Reset, db),
+                KeyCode::Char('o') | KeyCode::Char('O') => self.press(Button::OpenFile, db),
+                KeyCode::Char('c') | KeyCode::Char('C') => self.press(Button::ReadOnly, db),
+                KeyCode::Char('q') | KeyCode::Char('Q') => self.press(Button::Quit, db),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.menu_up();
+                    Operation::None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.menu_down();
+                    Operation::None
+                }
+                KeyCode::Enter => match self.buttons.get(self.selected_button as usize) {
+                    Some(button) => self.press(*button, db),
+                    None => Operation::None,
+                },
+                _ => Operation::None,
+            },
+            _ => Operation::None,
+        }
+    }
+}
+
+impl DatabaseUnreadable {
+    pub fn new() -> Self {
+        Self {
+            selected_button: 0,
+            buttons: UNREADABLE_BUTTONS,
+            path: String::new(),
+            error: String::new(),
+            failed_open_file: false,
+        }
+    }
+
+    // the buttons that aren't on the page don't do anything, their keys included
+    fn press(&mut self, button: Button, db: Database) -> Operation {
+        if !self.buttons.contains(&button) {
+            return Operation::None;
+        }
+        match button {
+            Button::Reset => self.reset(db),
+            Button::OpenFile => {
+                self.open_file();
+                Operation::None
+            }
+            Button::ReadOnly => self.continue_read_only(),
+            Button::Quit => Operation::Exit,
+        }
+    }
+
+    fn reset(&mut self, db: Database) -> Operation {
+        let result = {
+            // lock db
+            let mut db = db.lock().unwrap();
+            db.reset()
+        }; // release db
+        match result {
+            Ok(_) => Operation::Navigate("/first_load".to_string()),
+            Err(err) => {
+                self.error = err.to_string();
+                Operation::None
+            }
+        }
+    }
+
+    fn continue_read_only(&self) -> Operation {
+        // the database stays empty and nothing gets written back to the file
+        Operation::Navigate("/first_load".to_string())
+    }
+
+    fn open_file(&mut self) {
+        let (program, args) = OPENER;
+        self.failed_open_file = Command::new(program)
+            .args(args)
+            .arg(&self.path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .is_err();
+    }
+
+    fn menu_up(&mut self) {
+        self.selected_button = self.selected_button.saturating_sub(1);
+    }
+
+    fn menu_down(&mut self) {
+        let len = self.buttons.len() as u8;
+        self.selected_button = std::cmp::min(self.selected_button + 1, len - 1);
+    }
+}
@@ -0,0 +1,301 @@
+use tui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::input::{Event, EventSender, KeyCode};
+use crate::router::{
+    page::{MountOperation, MountResult, Page},
+    Params,
+};
+use crate::ui::{Api, Database, Frame, Operation};
+use crate::Ignore;
+use std::{process::Stdio, time::Duration};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+const MENU_BUTTON_LEN: u8 = 4;
+// a copier that hangs is given up on, the page would be waiting for an answer forever
+const COPY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[cfg(target_os = "macos")]
+const COPIER: (&str, &[&str]) = ("pbcopy", &[]);
+#[cfg(target_os = "windows")]
+const COPIER: (&str, &[&str]) = ("clip", &[]);
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const COPIER: (&str, &[&str]) = ("xclip", &["-selection", "clipboard"]);
+
+pub struct Error {
+    selected_button: u8,
+    failed: Option<String>,
+    // the error first, then whatever caused it
+    chain: Vec<String>,
+    // whether the last copy went through, nothing until one is tried
+    copied: Option<bool>,
+    // where the copier reports back to
+    event_sender: Option<EventSender>,
+}
+
+use async_trait::async_trait;
+#[async_trait]
+impl Page for Error {
+    async fn mount(
+        &mut self,
+        db: Database,
+        api: Api,
+        event_sender: EventSender,
+        mut params: Params,
+    ) -> MountResult {
+        self.selected_button = 0;
+        self.copied = None;
+        self.event_sender = Some(event_sender);
+        self.failed = params.remove("failed");
+        self.chain = params
+            .remove("error")
+            .map(|error| error.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        Ok(MountOperation::None)
+    }
+
+    async fn unmount(&mut self, db: Database, api: Api) {}
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let block = Block::default().title("Error").borders(Borders::ALL);
+        let main_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(10),
+                Constraint::Percentage(80),
+                Constraint::Percentage(10),
+            ])
+            .split(rect);
+        let center_rect = main_layout[1];
+        let center_layout = Layout::default()
+            .margin(1)
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Length(1),
+                Constraint::Min(2),
+                Constraint::Length(1),
+                Constraint::Length(4),
+                Constraint::Percentage(10),
+            ])
+            .split(center_rect);
+        let btn_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(center_layout[4]);
+
+        let title = Paragraph::new(match &self.failed {
+            Some(location) => format!("Something went wrong while opening \"{}\"", location),
+            None => "Something went wrong!".to_string(),
+        })
+        .block(Block::default())
+        .wrap(Wrap { trim: true })
+        .alignment(Alignment::Center);
+
+        let chain: Vec<_> = self
+            .chain
+            .iter()
+            .enumerate()
+            .map(|(index, cause)| {
+                if index == 0 {
+                    Spans::from(Span::styled(cause, Style::default().fg(Color::Red)))
+                } else {
+                    Spans::from(format!("caused by: {}", cause))
+                }
+            })
+            .collect();
+        let msg = Paragraph::new(chain)
+            .block(Block::default())
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center);
+
+        let btns = [
+            (
+                0,
+                Paragraph::new("<[R]etry>")
+                    .block(Block::default())
+                    .wrap(Wrap { trim: true })
+                    .alignment(Alignment::Center),
+            ),
+            (
+                1,
+                Paragraph::new("<[B]ack to last page>")
+                    .block(Block::default())
+                    .wrap(Wrap { trim: true })
+                    .alignment(Alignment::Center),
+            ),
+            (
+                2,
+                Paragraph::new("<[C]opy the details>")
+                    .block(Block::default())
+                    .wrap(Wrap { trim: true })
+                    .alignment(Alignment::Center),
+            ),
+            (
+                3,
+                Paragraph::new("<[Q]uit>")
+                    .block(Block::default())
+                    .wrap(Wrap { trim: true })
+                    .alignment(Alignment::Center),
+            ),
+        ]
+        .map(|btn| {
+            if btn.0 == self.selected_button {
+                btn.1.style(Style::default().fg(Color::Yellow))
+            } else {
+                btn.1
+            }
+        });
+
+        let mut btn_iter = btns.into_iter();
+        frame.render_widget(block, rect);
+        frame.render_widget(title, center_layout[1]);
+        frame.render_widget(msg, center_layout[2]);
+        if let Some(copied) = self.copied {
+            let copy_result = if copied {
+                Paragraph::new("The details are in the clipboard")
+                    .style(Style::default().fg(Color::Green))
+            } else {
+                Paragraph::new(format!(
+                    "Failed to copy the details, is \"{}\" installed?",
+                    COPIER.0
+                ))
+                .style(Style::default().fg(Color::Red))
+            };
+            frame.render_widget(
+                copy_result
+                    .block(Block::default())
+                    .wrap(Wrap { trim: true })
+                    .alignment(Alignment::Center),
+                center_layout[3],
+            );
+        }
+        frame.render_widget(btn_iter.next().unwrap(), btn_layout[0]);
+        frame.render_widget(btn_iter.next().unwrap(), btn_layout[1]);
+        frame.render_widget(btn_iter.next().unwrap(), btn_layout[2]);
+        frame.render_widget(btn_iter.next().unwrap(), btn_layout[3]);
+    }
+
+    async fn update(&mut self, event: Event, db: Database, api: Api) -> Operation {
+        match event {
+            Event::Input(event) => match event.code {
+                KeyCode::Char('r') | KeyCode::Char('R') => self.retry(),
+                KeyCode::Char('b') | KeyCode::Char('B') => Operation::NavigateBackward,
+                KeyCode::Char('c') | KeyCode::Char('C') => {
+                    self.copy_details();
+                    Operation::None
+                }
+                KeyCode::Char('q') | KeyCode::Char('Q') => Operation::Exit,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.menu_up();
+                    Operation::None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.menu_down();
+                    Operation::None
+                }
+                KeyCode::Enter => match self.selected_button {
+                    0 => self.retry(),
+                    1 => Operation::NavigateBackward,
+                    2 => {
+                        self.copy_details();
+                        Operation::None
+                    }
+                    3 => Operation::Exit,
+                    _ => Operation::None,
+                },
+                _ => Operation::None,
+            },
+            Event::Copied(copied) => {
+                self.copied = Some(copied);
+                Operation::None
+            }
+            _ => Operation::None,
+        }
+    }
+}
+
+impl Error {
+    pub fn new() -> Self {
+        Self {
+            selected_button: 0,
+            failed: None,
+            chain: Vec::new(),
+            copied: None,
+            event_sender: None,
+        }
+    }
+
+    fn retry(&self) -> Operation {
+        match &self.failed {
+            Some(location) => Operation::Navigate(location.clone()),
+            None => Operation::None,
+        }
+    }
+
+    fn details(&self) -> String {
+        let mut details = String::new();
+        if let Some(location) = &self.failed {
+            details.push_str(&format!("location: {}\n", location));
+        }
+        for (index, cause) in self.chain.iter().enumerate() {
+            if index == 0 {
+                details.push_str(&format!("error: {}\n", cause));
+            } else {
+                details.push_str(&format!("caused by: {}\n", cause));
+            }
+        }
+        details
+    }
+
+    // the copier runs in the background, the result comes back as `Event::Copied`
+    fn copy_details(&mut self) {
+        let event_sender = match &self.event_sender {
+            Some(event_sender) => event_sender.clone(),
+            None => return,
+        };
+        let (program, args) = COPIER;
+        let details = self.details();
+        let copy = async move {
+            let mut child = Command::new(program)
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .spawn()?;
+            // closing stdin is what tells the copier it has everything
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(details.as_bytes())
+                .await?;
+            child.wait().await
+        };
+        tokio::spawn(async move {
+            let copied = matches!(
+                tokio::time::timeout(COPY_TIMEOUT, copy).await,
+                Ok(Ok(status)) if status.success()
+            );
+            event_sender.send(Event::Copied(copied)).ignore();
+        });
+    }
+
+    fn menu_up(&mut self) {
+        self.selected_button = self.selected_button.saturating_sub(1);
+    }
+
+    fn menu_down(&mut self) {
+        self.selected_button = std::cmp::min(self.selected_button + 1, MENU_BUTTON_LEN - 1);
+    }
+}
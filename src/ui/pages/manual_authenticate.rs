@@ -6,7 +6,6 @@ use tui::{
 
 use tui_textarea::TextArea;
 
-use crate::api::members::Members;
 use crate::{API_KEY, APP_NAME, DARK_MODE};
 use qrcode::{EcLevel, QrCode, Version};
 
@@ -31,7 +30,7 @@ pub struct ManualAuthenticate<'a> {
     error_token: bool,
 }
 
-const AUTH_URL: &str = formatcp!("https://trello.com/1/authorize?expiration=1day&name={APP_NAME}&scope=read&response_type=token&key={API_KEY}");
+const AUTH_URL: &str = formatcp!("https://trello.com/1/authorize?expiration=1day&name={APP_NAME}&scope=read,write&response_type=token&key={API_KEY}");
 
 use async_trait::async_trait;
 
@@ -202,6 +201,10 @@ impl<'a> Page for ManualAuthenticate<'a> {
             }
         }
     }
+
+    fn captures_input(&self) -> bool {
+        self.show_enter_token_dialog
+    }
 }
 
 impl<'a> ManualAuthenticate<'a> {
@@ -366,16 +369,17 @@ impl<'a> ManualAuthenticate<'a> {
                     let members_req = {
                         let mut api = api.lock().unwrap();
                         api.auth(token.clone());
-                        api.members_me()
+                        api.backend().members_me()
                     };
-                    if let Ok(user) = members_req.send().await {
+                    if let Ok(user) = members_req.await {
                         let user_id = user.id.clone();
                         {
                             let mut db = db.lock().unwrap();
                             db.add_user_account(user, token).unwrap();
-                            db.set_active_account(user_id).unwrap();
-                            db.first_load = false;
+                            db.set_active_account(user_id.clone()).unwrap();
+                            db.set_first_load(false);
                         }
+                        api.lock().unwrap().set_account(Some(user_id));
                         self.set_show_enter_token_dialog(false);
                         Operation::Navigate("/".to_string())
                     } else {
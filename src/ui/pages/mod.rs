@@ -2,9 +2,12 @@ pub mod authenticate;
 pub mod board;
 pub mod boards;
 pub mod browser_authenticate;
+pub mod database_unreadable;
+pub mod error;
 pub mod first_load;
 pub mod home;
 pub mod manual_authenticate;
 pub mod not_found;
+pub mod pending_changes;
 pub mod token_expired;
 pub mod workspaces;
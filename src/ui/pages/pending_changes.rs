@@ -0,0 +1,235 @@
+use tui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+};
+
+use crate::input::{Event, EventSender, KeyCode};
+use crate::outbox::{Conflict, Entry, Resolution};
+use crate::router::{
+    page::{MountOperation, MountResult, Page},
+    Params,
+};
+use crate::ui::{Api, Database, Frame, Operation};
+
+const MENU_BUTTON_LEN: u8 = 3;
+
+pub struct PendingChanges {
+    selected_button: u8,
+    entries: Vec<Entry>,
+}
+
+use async_trait::async_trait;
+#[async_trait]
+impl Page for PendingChanges {
+    async fn mount(
+        &mut self,
+        db: Database,
+        api: Api,
+        event_sender: EventSender,
+        params: Params,
+    ) -> MountResult {
+        self.selected_button = 0;
+        self.reload(&api);
+        Ok(MountOperation::None)
+    }
+
+    async fn unmount(&mut self, db: Database, api: Api) {}
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let block = Block::default()
+            .title("Pending Changes")
+            .borders(Borders::ALL);
+        let layout = Layout::default()
+            .margin(1)
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(4),
+                Constraint::Length(1),
+            ])
+            .split(rect);
+        let btn_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ])
+            .split(layout[2]);
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let item = ListItem::new(entry.change.describe());
+                if entry.conflict.is_some() {
+                    item.style(Style::default().fg(Color::Red))
+                } else {
+                    item
+                }
+            })
+            .collect();
+        let list = List::new(items).block(
+            Block::default()
+                .title(format!("{} waiting to be sent", self.entries.len()))
+                .borders(Borders::BOTTOM),
+        );
+
+        let details = match self.conflict() {
+            Some(Conflict::Changed { theirs }) => format!(
+                "The card was changed by someone else, it's now \"{}\" in list {}",
+                theirs.name, theirs.id_list
+            ),
+            Some(Conflict::Rejected { reason }) => format!("Trello refused it: {}", reason),
+            Some(Conflict::Unanswered) => {
+                "Trello didn't answer when it was sent, check whether it went through".to_string()
+            }
+            None if self.entries.is_empty() => "Everything is sent!".to_string(),
+            None => "Waiting for the connection to come back...".to_string(),
+        };
+        let details = Paragraph::new(details)
+            .block(Block::default())
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center);
+
+        let btns = [
+            (
+                0,
+                Paragraph::new(match self.conflict() {
+                    Some(Conflict::Unanswered) => "<Send it again, keep [m]ine>",
+                    _ => "<Keep [m]ine>",
+                })
+                .block(Block::default())
+                .alignment(Alignment::Center),
+            ),
+            (
+                1,
+                Paragraph::new(match self.conflict() {
+                    Some(Conflict::Rejected { .. } | Conflict::Unanswered) => {
+                        "<Drop it, keep [t]heirs>"
+                    }
+                    _ => "<Keep [t]heirs>",
+                })
+                .block(Block::default())
+                .alignment(Alignment::Center),
+            ),
+            (
+                2,
+                Paragraph::new("<[B]ack>")
+                    .block(Block::default())
+                    .alignment(Alignment::Center),
+            ),
+        ]
+        .map(|btn| {
+            if btn.0 == self.selected_button {
+                btn.1.style(Style::default().fg(Color::Yellow))
+            } else {
+                btn.1
+            }
+        });
+
+        let mut btn_iter = btns.into_iter();
+        frame.render_widget(block, rect);
+        frame.render_widget(list, layout[0]);
+        frame.render_widget(details, layout[1]);
+        // there's nothing of theirs to overwrite when Trello refused the change
+        match self.conflict() {
+            Some(Conflict::Changed { .. } | Conflict::Unanswered) => {
+                frame.render_widget(btn_iter.next().unwrap(), btn_layout[0]);
+                frame.render_widget(btn_iter.next().unwrap(), btn_layout[1]);
+            }
+            Some(Conflict::Rejected { .. }) => {
+                btn_iter.next();
+                frame.render_widget(btn_iter.next().unwrap(), btn_layout[1]);
+            }
+            None => {
+                btn_iter.next();
+                btn_iter.next();
+            }
+        }
+        frame.render_widget(btn_iter.next().unwrap(), btn_layout[2]);
+    }
+
+    async fn update(&mut self, event: Event, db: Database, api: Api) -> Operation {
+        match event {
+            Event::Tick => {
+                self.reload(&api);
+                Operation::None
+            }
+            Event::Input(event) => match event.code {
+                KeyCode::Char('m') | KeyCode::Char('M') => {
+                    self.resolve(Resolution::KeepMine, &api);
+                    Operation::None
+                }
+                KeyCode::Char('t') | KeyCode::Char('T') => {
+                    self.resolve(Resolution::KeepTheirs, &api);
+                    Operation::None
+                }
+                KeyCode::Char('b') | KeyCode::Char('B') => Operation::NavigateBackward,
+                KeyCode::Left | KeyCode::Char('h') => {
+                    self.menu_left();
+                    Operation::None
+                }
+                KeyCode::Right | KeyCode::Char('l') => {
+                    self.menu_right();
+                    Operation::None
+                }
+                KeyCode::Enter => match self.selected_button {
+                    0 => {
+                        self.resolve(Resolution::KeepMine, &api);
+                        Operation::None
+                    }
+                    1 => {
+                        self.resolve(Resolution::KeepTheirs, &api);
+                        Operation::None
+                    }
+                    2 => Operation::NavigateBackward,
+                    _ => Operation::None,
+                },
+                _ => Operation::None,
+            },
+            _ => Operation::None,
+        }
+    }
+}
+
+impl PendingChanges {
+    pub fn new() -> Self {
+        Self {
+            selected_button: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    fn reload(&mut self, api: &Api) {
+        let outbox = api.lock().unwrap().outbox();
+        self.entries = outbox.entries();
+    }
+
+    fn conflict(&self) -> Option<&Conflict> {
+        self.entries.first().and_then(|entry| entry.conflict.as_ref())
+    }
+
+    fn resolve(&mut self, resolution: Resolution, api: &Api) {
+        if self.conflict().is_some() {
+            let outbox = api.lock().unwrap().outbox();
+            outbox.resolve(resolution);
+            self.reload(api);
+        }
+    }
+
+    fn menu_left(&mut self) -> bool {
+        if self.selected_button == 0 {
+            false
+        } else {
+            self.selected_button -= 1;
+            true
+        }
+    }
+
+    fn menu_right(&mut self) -> bool {
+        self.selected_button = std::cmp::min(self.selected_button + 1, MENU_BUTTON_LEN - 1);
+        true
+    }
+}
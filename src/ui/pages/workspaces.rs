@@ -4,23 +4,28 @@ use tui::{
     widgets::{Block, Borders, List, ListItem, ListState},
 };
 
-use crate::api::{members::Members, organizations::Organizations};
+use crate::cache::keys;
 use crate::input::{Event, EventSender, KeyCode};
-use crate::models::Organization;
+use crate::models::{Organization, OrganizationId, User};
 use crate::router::{
-    page::{MountOperation, MountResult, Page},
+    page::{MountOperation, MountResult, Page, Snapshot},
     Params,
 };
 use crate::ui::{
-    misc::layout::{center_rect_with_margin, rect_with_margin_top},
-    Api, Database, Frame, Operation,
+    misc::{
+        layout::{center_rect_with_margin, rect_with_margin_top},
+        skeleton,
+    },
+    workspace_boards_location, Api, Database, Frame, Operation,
 };
 
-use tokio::task::JoinSet;
-
 pub struct Workspaces {
     workspaces: Vec<Organization>,
+    loaded: bool,
+    // the workspaces last asked for, they depend on the member
+    requested: Option<Vec<OrganizationId>>,
     state: ListState,
+    event_sender: Option<EventSender>,
 }
 
 use async_trait::async_trait;
@@ -34,35 +39,27 @@ impl Page for Workspaces {
         params: Params,
     ) -> MountResult {
         self.workspaces.clear();
+        self.loaded = false;
+        self.requested = None;
         self.state.select(Some(0));
 
-        let members_req = {
+        let (members_req, cache) = {
             // lock api
             let api = api.lock().unwrap();
-            api.members_me()
+            (api.backend().members_me(), api.cache())
         }; // release api
-        let me = members_req.send().await?;
-        let mut futures = JoinSet::new();
-        {
-            // lock api
-            let api = api.lock().unwrap();
-            me.id_organizations
-                .into_iter()
-                .map(|id| api.organizations_get(&id).send())
-                .for_each(|f| {
-                    futures.spawn(f);
-                });
-        } // release api
-
-        while let Some(result) = futures.join_next().await {
-            self.workspaces.push(result??);
+        self.event_sender = Some(event_sender.clone());
+        // without a cached member the workspaces wait for it in `update`
+        if let Some(me) = cache.load(keys::MEMBERS_ME.to_string(), members_req, &event_sender) {
+            self.load(me.id_organizations, &api);
         }
-        self.workspaces.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
 
         Ok(MountOperation::None)
     }
 
-    async fn unmount(&mut self, db: Database, api: Api) {}
+    async fn unmount(&mut self, db: Database, api: Api) {
+        self.event_sender = None;
+    }
 
     fn draw(&mut self, frame: &mut Frame, rect: Rect) {
         let block = Block::default().title("Workspaces").borders(Borders::ALL);
@@ -70,11 +67,14 @@ impl Page for Workspaces {
         let list_block_rect = center_rect_with_margin(rect, 30, 1);
         let list_rect = rect_with_margin_top(list_block_rect, 2);
 
-        let recent_boards: Vec<ListItem> = self
-            .workspaces
-            .iter()
-            .map(|w| ListItem::new(w.display_name.clone()))
-            .collect();
+        let recent_boards: Vec<ListItem> = if self.loaded {
+            self.workspaces
+                .iter()
+                .map(|w| ListItem::new(w.display_name.clone()))
+                .collect()
+        } else {
+            skeleton::items(3)
+        };
 
         let workspaces_block = Block::default()
             .title("Select a workspace")
@@ -90,8 +90,27 @@ impl Page for Workspaces {
         frame.render_stateful_widget(workspaces_list, list_rect, &mut self.state);
     }
 
+    fn snapshot(&self) -> Option<Snapshot> {
+        Some(Box::new(self.state.selected()))
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        if let Ok(selected) = snapshot.downcast::<Option<usize>>() {
+            self.state.select(*selected);
+        }
+    }
+
     async fn update(&mut self, event: Event, db: Database, api: Api) -> Operation {
         match event {
+            Event::CacheRefreshed(key) => {
+                self.refresh(&key, api);
+                Operation::None
+            }
+            Event::CacheFailed(key, err)
+                if key == keys::MEMBERS_ME || key == keys::ORGANIZATIONS =>
+            {
+                Operation::Fail(err.into())
+            }
             Event::Input(event) => match event.code {
                 KeyCode::Up | KeyCode::Char('k') => {
                     self.up();
@@ -101,10 +120,12 @@ impl Page for Workspaces {
                     self.down();
                     Operation::None
                 }
-                KeyCode::Enter => {
-                    let org_id = self.workspaces[self.state.selected().unwrap()].id.clone();
-                    Operation::Navigate(format!("/w/{}/boards", org_id))
-                }
+                KeyCode::Enter => match self.workspaces.get(self.state.selected().unwrap()) {
+                    Some(workspace) => {
+                        Operation::Navigate(workspace_boards_location(&workspace.id))
+                    }
+                    None => Operation::None,
+                },
                 _ => Operation::None,
             },
             _ => Operation::None,
@@ -116,7 +137,10 @@ impl Workspaces {
     pub fn new() -> Self {
         Self {
             workspaces: Vec::new(),
+            loaded: false,
+            requested: None,
             state: ListState::default(),
+            event_sender: None,
         }
     }
 
@@ -133,4 +157,46 @@ impl Workspaces {
             self.state.select(Some(new_index))
         }
     }
+
+    // asks for the member's workspaces, again only when they turn out to be different ones
+    fn load(&mut self, ids: Vec<OrganizationId>, api: &Api) {
+        if self.requested.as_ref() == Some(&ids) {
+            return;
+        }
+        let (organizations_req, cache) = {
+            // lock api
+            let api = api.lock().unwrap();
+            (api.backend().organizations_batch(&ids), api.cache())
+        }; // release api
+        self.requested = Some(ids);
+        if let Some(event_sender) = &self.event_sender {
+            let key = keys::ORGANIZATIONS.to_string();
+            if let Some(workspaces) = cache.load(key, organizations_req, event_sender) {
+                self.set_workspaces(workspaces);
+            }
+        }
+    }
+
+    fn set_workspaces(&mut self, workspaces: Vec<Organization>) {
+        self.workspaces = workspaces;
+        self.workspaces.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        self.loaded = true;
+        if self.state.selected().unwrap_or(0) >= self.workspaces.len() {
+            self.state
+                .select(Some(self.workspaces.len().saturating_sub(1)));
+        }
+    }
+
+    fn refresh(&mut self, key: &str, api: Api) {
+        let cache = api.lock().unwrap().cache();
+        if key == keys::MEMBERS_ME {
+            if let Some(me) = cache.get::<User>(key) {
+                self.load(me.id_organizations, &api);
+            }
+        } else if key == keys::ORGANIZATIONS {
+            if let Some(fresh) = cache.get::<Vec<Organization>>(key) {
+                self.set_workspaces(fresh);
+            }
+        }
+    }
 }